@@ -0,0 +1,350 @@
+//! # wiremock-grpc-build
+//!
+//! Compiles `.proto` files with [`tonic_prost_build`] and, for every service, writes a
+//! [wiremock-grpc](https://docs.rs/wiremock-grpc) mock server with typed builders next to the
+//! generated tonic code. Adding an rpc to a proto makes it available on the mock right away.
+//!
+//! ```no_run
+//! // build.rs
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     wiremock_grpc_build::compile_protos("proto/hello.proto")?;
+//!
+//!     // or, to tweak the tonic code generation
+//!     wiremock_grpc_build::configure()
+//!         .tonic(|b| b.build_server(false))
+//!         .compile_protos(&["proto/hello.proto"], &["proto"])?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The mocks of a package are written to `{package}.wiremock.rs` and are included with
+//! `wiremock_grpc::include_mock!` in the module containing the tonic code for the same package:
+//!
+//! ```ignore
+//! mod hello {
+//!     tonic::include_proto!("hello");
+//!     wiremock_grpc::include_mock!("hello");
+//! }
+//!
+//! let server = hello::GreeterMockServer::start_default().await;
+//! ```
+//!
+//! Like the tonic code, the mocks of services declared without a package are written to
+//! `_.wiremock.rs` and included with `wiremock_grpc::include_mock!("_")`.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use prost::Message;
+use prost_types::{DescriptorProto, FileDescriptorSet};
+
+/// Configure the code generation.
+///
+/// Use [`compile_protos`] instead if you don't need to tweak anything.
+pub fn configure() -> Builder {
+    Builder {
+        tonic: tonic_prost_build::configure(),
+        out_dir: None,
+        file_descriptor_set_path: None,
+    }
+}
+
+/// Compile a `.proto` file, generating the tonic code and the mock servers.
+///
+/// Same as [`tonic_prost_build::compile_protos`]: the include directory will be the parent
+/// folder of the specified path.
+pub fn compile_protos(proto: impl AsRef<Path>) -> io::Result<()> {
+    let proto_path: &Path = proto.as_ref();
+    let proto_dir = proto_path
+        .parent()
+        .expect("proto file should reside in a directory");
+
+    configure().compile_protos(&[proto_path], &[proto_dir])
+}
+
+/// Builder to configure the tonic and mock code generation.
+pub struct Builder {
+    tonic: tonic_prost_build::Builder,
+    out_dir: Option<PathBuf>,
+    file_descriptor_set_path: Option<PathBuf>,
+}
+
+impl Builder {
+    /// Set the output directory of both the tonic and the mock code. Defaults to `OUT_DIR`.
+    pub fn out_dir(mut self, out_dir: impl AsRef<Path>) -> Self {
+        self.tonic = self.tonic.out_dir(out_dir.as_ref());
+        self.out_dir = Some(out_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Keep the descriptor set of the compiled files at `path`, eg. to load it at runtime with
+    /// `prost_reflect::DescriptorPool`. Not written by default.
+    pub fn file_descriptor_set_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.file_descriptor_set_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Customise the underlying [`tonic_prost_build::Builder`].
+    ///
+    /// Options changing the generated type names or paths (eg. `extern_path`) are not
+    /// reflected in the mock code.
+    pub fn tonic<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(tonic_prost_build::Builder) -> tonic_prost_build::Builder,
+    {
+        self.tonic = f(self.tonic);
+        self
+    }
+
+    /// Compile the `.proto` files, generating the tonic code and the mock servers.
+    pub fn compile_protos<P>(self, protos: &[P], includes: &[P]) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let out_dir = match self.out_dir {
+            Some(out_dir) => out_dir,
+            None => PathBuf::from(std::env::var("OUT_DIR").map_err(io::Error::other)?),
+        };
+
+        let descriptor_path = self
+            .file_descriptor_set_path
+            .clone()
+            .unwrap_or_else(|| out_dir.join("wiremock-grpc-build.bin"));
+        self.tonic
+            .file_descriptor_set_path(&descriptor_path)
+            .compile_protos(protos, includes)?;
+
+        let descriptor = fs::read(&descriptor_path)?;
+        if self.file_descriptor_set_path.is_none() {
+            fs::remove_file(&descriptor_path)?;
+        }
+        let fds = FileDescriptorSet::decode(descriptor.as_slice()).map_err(io::Error::other)?;
+
+        for (package, code) in generate(&fds) {
+            let file_name = if package.is_empty() {
+                "_".to_string()
+            } else {
+                package
+            };
+            fs::write(out_dir.join(format!("{file_name}.wiremock.rs")), code)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates the mock code of every package that defines at least one service.
+fn generate(fds: &FileDescriptorSet) -> BTreeMap<String, String> {
+    let types = message_types(fds);
+    let mut packages = BTreeMap::<String, String>::new();
+
+    for file in &fds.file {
+        let package = file.package();
+
+        for service in &file.service {
+            let code = packages.entry(package.to_string()).or_insert_with(|| {
+                "// This file is @generated by wiremock-grpc-build.\n".to_string()
+            });
+
+            let _ = writeln!(code, "wiremock_grpc::generate_svc! {{");
+            if !package.is_empty() {
+                let _ = writeln!(code, "    package {package};");
+            }
+            let _ = writeln!(code, "    service {} {{", service.name());
+            for method in &service.method {
+                let _ = writeln!(
+                    code,
+                    "        {}({}) -> {},",
+                    method.name(),
+                    rust_type(method.input_type(), package, &types),
+                    rust_type(method.output_type(), package, &types),
+                );
+            }
+            let _ = writeln!(code, "    }}");
+            let _ = writeln!(code, "}}");
+        }
+    }
+
+    packages
+}
+
+/// Location of a message: its package and the names of the message and its parents.
+struct MessageType {
+    package: String,
+    path: Vec<String>,
+}
+
+/// Indexes every message by its fully qualified name (eg. `.hello.HelloRequest`).
+fn message_types(fds: &FileDescriptorSet) -> HashMap<String, MessageType> {
+    fn visit(
+        types: &mut HashMap<String, MessageType>,
+        package: &str,
+        parent: &[String],
+        messages: &[DescriptorProto],
+    ) {
+        for message in messages {
+            let mut path = parent.to_vec();
+            path.push(message.name().to_string());
+
+            let mut full_name = String::from(".");
+            if !package.is_empty() {
+                full_name.push_str(package);
+                full_name.push('.');
+            }
+            full_name.push_str(&path.join("."));
+
+            visit(types, package, &path, &message.nested_type);
+            types.insert(
+                full_name,
+                MessageType {
+                    package: package.to_string(),
+                    path,
+                },
+            );
+        }
+    }
+
+    let mut types = HashMap::new();
+    for file in &fds.file {
+        visit(&mut types, file.package(), &[], &file.message_type);
+    }
+    types
+}
+
+/// Resolves the Rust path of a message the way `prost-build` names it, relative to the
+/// module holding the code of `package`.
+fn rust_type(full_name: &str, package: &str, types: &HashMap<String, MessageType>) -> String {
+    if let Some(name) = full_name.strip_prefix(".google.protobuf.") {
+        return match name {
+            "Empty" => "()".to_string(),
+            _ => format!("::prost_types::{name}"),
+        };
+    }
+
+    let Some(ty) = types.get(full_name) else {
+        // not part of the descriptor set, best effort.
+        return full_name
+            .rsplit('.')
+            .next()
+            .unwrap_or(full_name)
+            .to_string();
+    };
+
+    let current: Vec<&str> = package.split('.').filter(|p| !p.is_empty()).collect();
+    let target: Vec<&str> = ty.package.split('.').filter(|p| !p.is_empty()).collect();
+    let common = current
+        .iter()
+        .zip(&target)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut segments = vec!["super".to_string(); current.len() - common];
+    segments.extend(target[common..].iter().map(|p| to_snake_case(p)));
+
+    let (name, parents) = ty.path.split_last().expect("message has a name");
+    segments.extend(parents.iter().map(|p| to_snake_case(p)));
+    segments.push(to_upper_camel_case(name));
+
+    segments.join("::")
+}
+
+/// Splits an identifier into words: `HTTPServer_v2` -> `HTTP`, `Server`, `v2`.
+fn words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        if ch.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied();
+            if !prev.is_uppercase() || next.is_some_and(|n| n.is_lowercase()) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(ch);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+fn to_snake_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn to_upper_camel_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|w| {
+            let mut chars = w.chars();
+            let first = chars.next().map(|c| c.to_uppercase().collect::<String>());
+            first.unwrap_or_default() + &chars.as_str().to_lowercase()
+        })
+        .collect()
+}
+
+#[test]
+fn test_rust_type() {
+    let mut types = HashMap::new();
+    let mut add = |full_name: &str, package: &str, path: &[&str]| {
+        types.insert(
+            full_name.to_string(),
+            MessageType {
+                package: package.to_string(),
+                path: path.iter().map(|s| s.to_string()).collect(),
+            },
+        );
+    };
+    add(".hello.HelloRequest", "hello", &["HelloRequest"]);
+    add(".hello.Outer.Inner", "hello", &["Outer", "Inner"]);
+    add(
+        ".hello.extended.HTTPReply",
+        "hello.extended",
+        &["HTTPReply"],
+    );
+    add(".other.Msg", "other", &["Msg"]);
+
+    assert_eq!(
+        rust_type(".hello.HelloRequest", "hello", &types),
+        "HelloRequest"
+    );
+    assert_eq!(
+        rust_type(".hello.Outer.Inner", "hello", &types),
+        "outer::Inner"
+    );
+    assert_eq!(
+        rust_type(".hello.extended.HTTPReply", "hello", &types),
+        "extended::HttpReply"
+    );
+    assert_eq!(
+        rust_type(".hello.HelloRequest", "hello.extended", &types),
+        "super::HelloRequest"
+    );
+    assert_eq!(
+        rust_type(".other.Msg", "hello", &types),
+        "super::other::Msg"
+    );
+    assert_eq!(rust_type(".google.protobuf.Empty", "hello", &types), "()");
+    assert_eq!(
+        rust_type(".google.protobuf.Timestamp", "hello", &types),
+        "::prost_types::Timestamp"
+    );
+}
@@ -0,0 +1,13 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // the tests start the server with the descriptor set of the hello service
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+    tonic_prost_build::configure()
+        .build_server(false)
+        .file_descriptor_set_path(out_dir.join("hello.bin"))
+        .compile_protos(
+            &["../wiremock-grpc/proto/hello.proto"],
+            &["../wiremock-grpc/proto"],
+        )?;
+
+    Ok(())
+}
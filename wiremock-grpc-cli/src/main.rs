@@ -0,0 +1,126 @@
+//! # wiremock-grpc
+//!
+//! A standalone gRPC mock server: serves the stub mappings (JSON or YAML files) found in a
+//! directory, using the descriptor set of the mocked services to read and write the messages.
+//!
+//! ```sh
+//! protoc --include_imports --descriptor_set_out=hello.bin hello.proto
+//! wiremock-grpc --descriptor-set hello.bin --mappings ./mappings --port 50051
+//! ```
+
+use std::{
+    error::Error,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use log::info;
+use wiremock_grpc::{prost_reflect::DescriptorPool, GrpcServer};
+
+mod stubs;
+
+/// Serve gRPC stubs from JSON or YAML mapping files
+#[derive(Debug, Parser)]
+#[command(name = "wiremock-grpc", version, about)]
+struct Args {
+    /// Descriptor set of the mocked services (`protoc --include_imports --descriptor_set_out`).
+    /// Repeat, or separate with commas, to load several.
+    #[arg(
+        short,
+        long = "descriptor-set",
+        env = "WIREMOCK_GRPC_DESCRIPTOR_SET",
+        value_delimiter = ',',
+        required = true
+    )]
+    descriptor_sets: Vec<PathBuf>,
+
+    /// Directory containing the stub mappings (`*.json`, `*.yaml` or `*.yml`)
+    #[arg(
+        short,
+        long,
+        env = "WIREMOCK_GRPC_MAPPINGS",
+        default_value = "mappings"
+    )]
+    mappings: PathBuf,
+
+    /// Address to listen on
+    #[arg(long, env = "WIREMOCK_GRPC_HOST", default_value = "0.0.0.0")]
+    host: IpAddr,
+
+    /// Port to listen on
+    #[arg(short, long, env = "WIREMOCK_GRPC_PORT", default_value_t = 50051)]
+    port: u16,
+
+    /// Serve the admin API (HTTP/JSON) on this port, eg. to add mappings from the tests
+    #[arg(long, env = "WIREMOCK_GRPC_ADMIN_PORT")]
+    admin_port: Option<u16>,
+
+    /// Also accept gRPC-Web calls (HTTP/1.1, CORS allowed from any origin), eg. from a browser
+    #[arg(long, env = "WIREMOCK_GRPC_WEB")]
+    grpc_web: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    // the services are also described by the reflection service, eg. for `grpcurl`
+    let mut server = GrpcServer::with_addr(SocketAddr::new(args.host, args.port)).with_health();
+    let mut pool = DescriptorPool::new();
+    for path in &args.descriptor_sets {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("unable to read `{}`: {e}", path.display()))?;
+        pool.decode_file_descriptor_set(bytes.as_slice())
+            .map_err(|e| format!("invalid descriptor set `{}`: {e}", path.display()))?;
+        server = server.with_reflection(bytes);
+    }
+    if args.grpc_web {
+        server = server.with_grpc_web();
+    }
+
+    let rules = stubs::load(&args.mappings, &pool)?;
+
+    let mut server = server.start().await;
+    for rule in &rules {
+        server.setup(rule.clone());
+    }
+    info!(
+        "Serving {} mapping(s) from {} on {}",
+        rules.len(),
+        args.mappings.display(),
+        server.address()
+    );
+
+    if let Some(port) = args.admin_port {
+        let address = server
+            .start_admin(SocketAddr::new(args.host, port), pool)
+            .await?;
+        info!("Admin API listening on http://{address}/__admin");
+    }
+
+    shutdown_signal().await;
+    info!("Shutting down");
+
+    // the rules are not expectations here, unmatched ones must not fail the shutdown
+    server.reset();
+    Ok(())
+}
+
+/// Ctrl-C, or SIGTERM (eg. `docker stop`).
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate()).expect("Unable to listen to SIGTERM");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,64 @@
+//! Loading the stub mapping files.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use wiremock_grpc::{prost_reflect::DescriptorPool, Mapping, MockBuilder};
+
+/// A mapping file holds a single mapping, a list of mappings, or `{ "mappings": [...] }`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MappingFile {
+    Wrapped { mappings: Vec<Mapping> },
+    Many(Vec<Mapping>),
+    One(Box<Mapping>),
+}
+
+impl MappingFile {
+    fn into_mappings(self) -> Vec<Mapping> {
+        match self {
+            MappingFile::Wrapped { mappings } | MappingFile::Many(mappings) => mappings,
+            MappingFile::One(mapping) => vec![*mapping],
+        }
+    }
+}
+
+/// Reads every mapping file of `dir` (sorted by name) and builds the rules.
+pub(crate) fn load(dir: &Path, pool: &DescriptorPool) -> Result<Vec<MockBuilder>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("unable to read `{}`: {e}", dir.display()))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("json" | "yaml" | "yml")
+                )
+        })
+        .collect();
+    files.sort();
+
+    let mut rules = Vec::new();
+    for file in files {
+        let error =
+            |e: &dyn std::fmt::Display| format!("invalid mapping `{}`: {e}", file.display());
+
+        let content = fs::read_to_string(&file).map_err(|e| error(&e))?;
+        let mappings = if file.extension().is_some_and(|e| e == "json") {
+            serde_json::from_str::<MappingFile>(&content).map_err(|e| error(&e))?
+        } else {
+            serde_yaml::from_str::<MappingFile>(&content).map_err(|e| error(&e))?
+        };
+
+        for mapping in mappings.into_mappings() {
+            rules.push(mapping.to_rule(pool).map_err(|e| error(&e))?);
+        }
+    }
+
+    Ok(rules)
+}
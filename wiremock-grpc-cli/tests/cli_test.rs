@@ -0,0 +1,164 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+use std::{
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use hello::{greeter_client::GreeterClient, HelloRequest, WeatherRequest};
+use tonic::{transport::Channel, Code, Request};
+
+const DESCRIPTOR_SET: &str = concat!(env!("OUT_DIR"), "/hello.bin");
+
+/// Kills the server when the test ends, even on failure.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn mappings_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wiremock-grpc-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, content) in files {
+        std::fs::write(dir.join(file), content).unwrap();
+    }
+    dir
+}
+
+fn command(mappings: &Path, port: u16) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_wiremock-grpc"));
+    command
+        .args(["--descriptor-set", DESCRIPTOR_SET, "--host", "127.0.0.1"])
+        .arg("--mappings")
+        .arg(mappings)
+        .arg("--port")
+        .arg(port.to_string());
+    command
+}
+
+async fn connect(port: u16) -> GreeterClient<Channel> {
+    let started = Instant::now();
+    loop {
+        match Channel::from_shared(format!("http://127.0.0.1:{port}"))
+            .unwrap()
+            .connect()
+            .await
+        {
+            Ok(channel) => return GreeterClient::new(channel),
+            Err(e) if started.elapsed() > Duration::from_secs(10) => {
+                panic!("The server did not start: {e}")
+            }
+            Err(_) => tokio::time::sleep(Duration::from_millis(50)).await,
+        }
+    }
+}
+
+fn unused_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+#[tokio::test]
+async fn serves_the_mappings() {
+    let dir = mappings_dir(
+        "serves",
+        &[
+            (
+                "hello.json",
+                r#"{
+                    "request": {
+                        "path": "/hello.Greeter/SayHello",
+                        "headers": { "x-session-id": "abc123" },
+                        "body": { "contains": { "name": "Alice" } }
+                    },
+                    "response": {
+                        "body": { "message": "Hello Alice" },
+                        "headers": { "x-server": "mock" }
+                    }
+                }"#,
+            ),
+            (
+                "weather.yaml",
+                "
+mappings:
+  - request:
+      path: /hello.Greeter/WeatherInfo
+      body:
+        equalTo: { city: Atlantis }
+    response:
+      status: NOT_FOUND
+      delayMs: 200
+",
+            ),
+            ("notes.txt", "not a mapping"),
+        ],
+    );
+    let port = unused_port();
+    let _server = Server(command(&dir, port).spawn().unwrap());
+    let mut client = connect(port).await;
+
+    let mut request = Request::new(HelloRequest {
+        name: "Alice".into(),
+    });
+    request
+        .metadata_mut()
+        .insert("x-session-id", "abc123".parse().unwrap());
+    let response = client.say_hello(request).await.unwrap();
+    assert_eq!("mock", response.metadata().get("x-server").unwrap());
+    assert_eq!("Hello Alice", response.into_inner().message);
+
+    // the header is missing
+    let status = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+
+    let started = Instant::now();
+    let status = client
+        .weather_info(WeatherRequest {
+            city: "Atlantis".into(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::NotFound, status.code());
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn rejects_invalid_mappings() {
+    let dir = mappings_dir(
+        "invalid",
+        &[(
+            "unknown.json",
+            r#"{ "request": { "path": "/hello.Greeter/SayGoodbye" } }"#,
+        )],
+    );
+
+    let output = command(&dir, unused_port())
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown.json"), "{stderr}");
+    assert!(stderr.contains("SayGoodbye"), "{stderr}");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
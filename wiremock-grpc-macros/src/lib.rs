@@ -1,18 +1,20 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
 use syn::{
-    braced,
+    braced, parenthesized,
     parse::{Parse, ParseStream},
-    punctuated::Punctuated,
-    Ident, Result, Token,
+    Attribute, Ident, LitStr, Result, Token, Type,
 };
 
+mod proto;
+
 /// Generates a complete mock gRPC server with RPC method builders.
 ///
 /// This macro creates:
 /// - A mock server struct (`{ServiceName}MockServer` or custom name with `as`)
-/// - An extension trait for `WhenBuilder` with `path_{method_name}` methods
+/// - An extension trait for `WhenBuilder` with `path_{method_name}` methods, and
+///   `any_{service_name}_method` matching every rpc of the service
 ///
 /// # Syntax
 ///
@@ -44,11 +46,90 @@ use syn::{
 /// }
 /// ```
 ///
+/// Each rpc can optionally declare its request and response types. The generated
+/// `path_{method_name}` then returns a `TypedWhenBuilder` whose `return_body` only accepts
+/// the response type and which can match on the request message:
+///
+/// ```
+/// # macro_rules! generate_svc {
+/// #     ($($tt:tt)*) => {};
+/// # }
+/// generate_svc! {
+///     package hello;
+///     service Greeter {
+///         SayHello(HelloRequest) -> HelloReply,
+///         WeatherInfo(WeatherRequest) -> WeatherReply,
+///     }
+/// }
+/// ```
+///
+/// Or read the package, service and rpc names straight from a `.proto` file
+/// (the path is relative to the crate's `Cargo.toml`):
+///
+/// ```
+/// # macro_rules! generate_svc {
+/// #     ($($tt:tt)*) => {};
+/// # }
+/// generate_svc!(proto = "proto/hello.proto", service = "Greeter");
+///
+/// // with a custom server name
+/// generate_svc!(proto = "proto/hello.proto", service = "Greeter", server = MyMockServer);
+/// ```
+///
+/// All rpc methods of the service are generated, or of every service of the file when `service`
+/// is omitted. Compilation fails when the file cannot be read or does not define the named service.
+///
+/// ```compile_fail
+/// // error: service `Unknown` not found in `../wiremock-grpc/proto/hello.proto`
+/// wiremock_grpc_macros::generate_svc!(
+///     proto = "../wiremock-grpc/proto/hello.proto",
+///     service = "Unknown"
+/// );
+/// ```
+///
+/// # Multiple Services
+///
+/// Several services can be declared in one invocation, they are all served by one mock server
+/// named after the first service (or the `as` name given to any of them). A `package` applies to
+/// every service declared after it and can be left out, or empty, for services without a package.
+/// Attributes (including doc comments) can be added to each rpc:
+///
+/// ```
+/// # macro_rules! generate_svc {
+/// #     ($($tt:tt)*) => {};
+/// # }
+/// generate_svc! {
+///     package hello;
+///     service Greeter as MyMockServer {
+///         SayHello,
+///     }
+///
+///     package hello.extended;
+///     service ExtendedGreeter {
+///         /// Only available on unix
+///         #[cfg(unix)]
+///         SayHello,
+///     }
+///
+///     package;
+///     service Ping {
+///         Ping,
+///     }
+/// }
+/// ```
+///
+/// When two services have an rpc with the same name, pick the `path_*` method using the trait:
+/// `GreeterTypeSafeExt::path_say_hello(&MockBuilder::when())`.
+///
 /// # Generated Code
 ///
 /// The macro generates:
-/// - `{ServiceName}MockServer` (or custom name) - the mock server struct
-/// - `{ServiceName}TypeSafeExt` trait with `path_{method_name}` methods
+/// - `{ServiceName}MockServer` (or custom name) - the mock server struct, started with
+///   `start_default()`, `start(port)`, `start_with_addr(addr)`, `start_in_memory()`,
+///   `start_uds(path)`, `start_with(server)` or `start_blocking()` (without an async runtime),
+///   and `shared()` for one server shared by the tests of a process
+/// - `{ServiceName}TypeSafeExt` trait with `path_{method_name}` methods and
+///   `any_{service_name}_method`, one per service
 ///
 /// # Example
 ///
@@ -92,94 +173,274 @@ use syn::{
 /// ```
 #[proc_macro]
 pub fn generate_svc(input: TokenStream) -> TokenStream {
-    let service_def = syn::parse_macro_input!(input as ServiceDefinition);
-    service_def.generate().into()
+    let mock_def = syn::parse_macro_input!(input as MockDefinition);
+    mock_def.generate().into()
+}
+
+/// Every service served by one generated mock server.
+struct MockDefinition {
+    server_name: Ident,
+    services: Vec<ServiceDefinition>,
+    /// Absolute path of the `.proto` file the definition was read from, if any.
+    proto_file: Option<String>,
 }
 
 struct ServiceDefinition {
     package: String,
     service_name: Ident,
-    server_name: Ident,
-    methods: Punctuated<Ident, Token![,]>,
+    methods: Vec<MethodDefinition>,
 }
 
-impl Parse for ServiceDefinition {
+impl Parse for MockDefinition {
     fn parse(input: ParseStream) -> Result<Self> {
-        // package keyword (not used)
-        let _package_kw: Ident = input.parse()?;
-        if _package_kw != "package" {
-            return Err(syn::Error::new(
-                _package_kw.span(),
-                "expected `package` keyword",
-            ));
+        if input.peek(Ident) && input.peek2(Token![=]) {
+            return Self::parse_proto_args(input);
         }
 
-        // parse the package name (x.y.z)
-        let first: Ident = input.parse()?;
-        let mut package = first.to_string();
-        while input.peek(Token![.]) {
-            let _dot: Token![.] = input.parse()?;
-            let next: Ident = input.parse()?;
-            package.push('.');
-            package.push_str(&next.to_string());
-        }
+        // the package applies to every service declared after it, none by default
+        let mut package = String::new();
+        let mut server_name: Option<Ident> = None;
+        let mut services: Vec<ServiceDefinition> = Vec::new();
+
+        while !input.is_empty() {
+            let keyword: Ident = input.parse()?;
+
+            if keyword == "package" {
+                package = String::new();
+                // parse the package name (x.y.z), if any
+                if input.peek(Ident) {
+                    let first: Ident = input.parse()?;
+                    package.push_str(&first.to_string());
+                    while input.peek(Token![.]) {
+                        let _dot: Token![.] = input.parse()?;
+                        let next: Ident = input.parse()?;
+                        package.push('.');
+                        package.push_str(&next.to_string());
+                    }
+                }
+                let _semi: Token![;] = input.parse()?;
+                continue;
+            }
 
-        let _semi: Token![;] = input.parse()?;
+            // service <name> [as <custom name>]
+            if keyword != "service" {
+                return Err(syn::Error::new(
+                    keyword.span(),
+                    "expected `package` or `service` keyword",
+                ));
+            }
+            let service_name: Ident = input.parse()?;
+
+            if input.peek(Token![as]) {
+                let _as: Token![as] = input.parse()?;
+                let name: Ident = input.parse()?;
+                if let Some(existing) = &server_name {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("the mock server is already named `{existing}`"),
+                    ));
+                }
+                server_name = Some(name);
+            }
 
-        // next line: service <name> [as <custom name>]
-        let _service_kw: Ident = input.parse()?;
-        if _service_kw != "service" {
-            return Err(syn::Error::new(
-                _service_kw.span(),
-                "expected `service` keyword",
-            ));
+            let content;
+            braced!(content in input);
+            let methods = content.parse_terminated(MethodDefinition::parse, Token![,])?;
+
+            let service = ServiceDefinition {
+                package: package.clone(),
+                service_name,
+                methods: methods.into_iter().collect(),
+            };
+            if services
+                .iter()
+                .any(|s| s.service_name == service.service_name)
+            {
+                return Err(syn::Error::new(
+                    service.service_name.span(),
+                    format!(
+                        "service `{}` is declared more than once",
+                        service.service_name
+                    ),
+                ));
+            }
+            services.push(service);
         }
-        let service_name: Ident = input.parse()?;
 
-        let server_name = if input.peek(Token![as]) {
-            let _as: Token![as] = input.parse()?;
-            input.parse()?
+        let Some(first) = services.first() else {
+            return Err(input.error("expected at least one `service`"));
+        };
+        let server_name =
+            server_name.unwrap_or_else(|| format_ident!("{}MockServer", first.service_name));
+
+        Ok(MockDefinition {
+            server_name,
+            services,
+            proto_file: None,
+        })
+    }
+}
+
+/// `Name` or `Name(RequestType) -> ResponseType`, optionally preceded by attributes.
+struct MethodDefinition {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    types: Option<(Type, Type)>,
+}
+
+impl Parse for MethodDefinition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name: Ident = input.parse()?;
+
+        let types = if input.peek(syn::token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let request: Type = content.parse()?;
+            let _arrow: Token![->] = input.parse()?;
+            let response: Type = input.parse()?;
+            Some((request, response))
         } else {
-            format_ident!("{}MockServer", service_name)
+            None
         };
 
-        let content;
-        braced!(content in input);
+        Ok(MethodDefinition { attrs, name, types })
+    }
+}
+
+impl MockDefinition {
+    /// Parses `proto = "path/to/file.proto" [, service = "Name"] [, server = Ident]`.
+    fn parse_proto_args(input: ParseStream) -> Result<Self> {
+        let mut proto: Option<LitStr> = None;
+        let mut service: Option<LitStr> = None;
+        let mut server: Option<Ident> = None;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            let _eq: Token![=] = input.parse()?;
+            match key.to_string().as_str() {
+                "proto" => proto = Some(input.parse()?),
+                "service" => service = Some(input.parse()?),
+                "server" => server = Some(input.parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected one of `proto`, `service` or `server`",
+                    ))
+                }
+            }
 
-        let methods = content.parse_terminated(Ident::parse, Token![,])?;
+            if !input.is_empty() {
+                let _comma: Token![,] = input.parse()?;
+            }
+        }
 
-        Ok(ServiceDefinition {
-            package,
-            service_name,
-            server_name,
-            methods,
+        let proto = proto.ok_or_else(|| input.error("missing `proto = \"<path>\"`"))?;
+
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let path = std::path::Path::new(&manifest_dir).join(proto.value());
+        let source = std::fs::read_to_string(&path).map_err(|e| {
+            syn::Error::new(
+                proto.span(),
+                format!("unable to read `{}`: {e}", path.display()),
+            )
+        })?;
+        let file = proto::parse(&source).map_err(|e| {
+            syn::Error::new(
+                proto.span(),
+                format!("unable to parse `{}`: {e}", path.display()),
+            )
+        })?;
+
+        // every service of the file unless one is named
+        let services: Vec<&proto::ProtoService> = match &service {
+            Some(service) => {
+                let Some(svc) = file.services.iter().find(|s| s.name == service.value()) else {
+                    let available = file
+                        .services
+                        .iter()
+                        .map(|s| s.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    return Err(syn::Error::new(
+                        service.span(),
+                        format!(
+                            "service `{}` not found in `{}` (available: {})",
+                            service.value(),
+                            proto.value(),
+                            if available.is_empty() {
+                                "none"
+                            } else {
+                                &available
+                            }
+                        ),
+                    ));
+                };
+                vec![svc]
+            }
+            None => file.services.iter().collect(),
+        };
+
+        let Some(first) = services.first() else {
+            return Err(syn::Error::new(
+                proto.span(),
+                format!("no service found in `{}`", proto.value()),
+            ));
+        };
+        let span = service.as_ref().map_or(Span::call_site(), |s| s.span());
+
+        Ok(MockDefinition {
+            server_name: server.unwrap_or_else(|| format_ident!("{}MockServer", first.name)),
+            services: services
+                .iter()
+                .map(|svc| ServiceDefinition {
+                    package: file.package.clone(),
+                    service_name: Ident::new(&svc.name, span),
+                    methods: svc
+                        .methods
+                        .iter()
+                        .map(|m| MethodDefinition {
+                            attrs: Vec::new(),
+                            name: Ident::new(&m.name, Span::call_site()),
+                            types: None,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            proto_file: Some(path.display().to_string()),
         })
     }
 }
 
 impl ServiceDefinition {
-    fn generate(&self) -> TokenStream2 {
-        let ext_trait = self.generate_ext_trait();
-        let mock_server = self.generate_mock_server();
-
-        quote! {
-            #ext_trait
-            #mock_server
+    /// The fully qualified service name, eg. `hello.Greeter`.
+    fn prefix(&self) -> String {
+        if self.package.is_empty() {
+            self.service_name.to_string()
+        } else {
+            format!("{}.{}", self.package, self.service_name)
         }
     }
 
     fn generate_ext_trait(&self) -> TokenStream2 {
         let trait_name = format_ident!("{}TypeSafeExt", self.service_name);
-        let package = &self.package;
-        let service_name = &self.service_name;
+        let prefix = self.prefix();
 
         let method_signatures: Vec<_> = self
             .methods
             .iter()
             .map(|method| {
-                let fn_name = format_ident!("path_{}", to_snake_case(&method.to_string()));
-                quote! {
-                    fn #fn_name(&self) -> Self;
+                let fn_name = format_ident!("path_{}", to_snake_case(&method.name.to_string()));
+                let attrs = &method.attrs;
+                match &method.types {
+                    Some((req, resp)) => quote! {
+                        #(#attrs)*
+                        fn #fn_name(&self) -> wiremock_grpc::TypedWhenBuilder<#req, #resp>;
+                    },
+                    None => quote! {
+                        #(#attrs)*
+                        fn #fn_name(&self) -> Self;
+                    },
                 }
             })
             .collect();
@@ -188,33 +449,123 @@ impl ServiceDefinition {
             .methods
             .iter()
             .map(|method| {
-                let fn_name = format_ident!("path_{}", to_snake_case(&method.to_string()));
-                let path = format!("/{}.{}/{}", package, service_name, method);
-                quote! {
-                    fn #fn_name(&self) -> Self {
-                        #[expect(deprecated)]
-                        self.path(#path)
-                    }
+                let fn_name = format_ident!("path_{}", to_snake_case(&method.name.to_string()));
+                let path = format!("/{}/{}", prefix, method.name);
+                // docs and deprecation only apply to the trait declaration
+                let attrs = method
+                    .attrs
+                    .iter()
+                    .filter(|a| !a.path().is_ident("doc") && !a.path().is_ident("deprecated"));
+                match &method.types {
+                    Some((req, resp)) => quote! {
+                        #(#attrs)*
+                        fn #fn_name(&self) -> wiremock_grpc::TypedWhenBuilder<#req, #resp> {
+                            #[expect(deprecated)]
+                            let when = self.path(#path);
+                            wiremock_grpc::TypedWhenBuilder::from(when)
+                        }
+                    },
+                    None => quote! {
+                        #(#attrs)*
+                        fn #fn_name(&self) -> Self {
+                            #[expect(deprecated)]
+                            self.path(#path)
+                        }
+                    },
                 }
             })
             .collect();
 
+        let any_method = format_ident!(
+            "any_{}_method",
+            to_snake_case(&self.service_name.to_string())
+        );
+        let any_method_doc = format!(" Match the requests to any rpc of `{prefix}`.");
+        let service_path = format!("/{prefix}/");
+
         quote! {
             pub trait #trait_name {
                 #(#method_signatures)*
+
+                #[doc = #any_method_doc]
+                fn #any_method(&self) -> wiremock_grpc::WhenBuilder;
             }
 
             impl #trait_name for wiremock_grpc::WhenBuilder {
                 #(#method_impls)*
+
+                fn #any_method(&self) -> wiremock_grpc::WhenBuilder {
+                    self.path_prefix(#service_path)
+                }
+            }
+        }
+    }
+}
+
+impl MockDefinition {
+    fn generate(&self) -> TokenStream2 {
+        let ext_traits = self.services.iter().map(|s| s.generate_ext_trait());
+        let mock_server = self.generate_mock_server();
+        // Rebuild whenever the proto file changes.
+        let track_proto = self.proto_file.as_ref().map(|path| {
+            quote! {
+                const _: &[u8] = include_bytes!(#path);
             }
+        });
+
+        quote! {
+            #track_proto
+            #(#ext_traits)*
+            #mock_server
         }
     }
 
     fn generate_mock_server(&self) -> TokenStream2 {
         let server_name = &self.server_name;
-        let package = &self.package;
-        let service_name = &self.service_name;
-        let prefix = format!("{}.{}", package, service_name);
+        let prefix = self.services[0].prefix();
+        let prefixes = self.services.iter().map(ServiceDefinition::prefix);
+
+        // The server is registered once per service, under each service name.
+        let routes: Vec<_> = self
+            .services
+            .iter()
+            .skip(1)
+            .map(|service| {
+                let route = format_ident!("{}Route", service.service_name);
+                let prefix = service.prefix();
+                quote! {
+                    #[derive(Clone)]
+                    struct #route(#server_name);
+
+                    impl<B> wiremock_grpc::tonic::codegen::Service<wiremock_grpc::tonic::codegen::http::Request<B>> for #route
+                    where
+                        B: wiremock_grpc::http_body::Body + Send + 'static,
+                        B::Error: Into<wiremock_grpc::tonic::codegen::StdError> + Send + 'static,
+                    {
+                        type Response = wiremock_grpc::tonic::codegen::http::Response<wiremock_grpc::tonic::body::Body>;
+                        type Error = ::std::convert::Infallible;
+                        type Future = wiremock_grpc::tonic::codegen::BoxFuture<Self::Response, Self::Error>;
+
+                        fn poll_ready(
+                            &mut self,
+                            _cx: &mut ::std::task::Context<'_>,
+                        ) -> ::std::task::Poll<Result<(), Self::Error>> {
+                            ::std::task::Poll::Ready(Ok(()))
+                        }
+
+                        fn call(&mut self, req: wiremock_grpc::tonic::codegen::http::Request<B>) -> Self::Future {
+                            self.0.handle_request(req)
+                        }
+                    }
+
+                    impl wiremock_grpc::tonic::server::NamedService for #route {
+                        const NAME: &'static str = #prefix;
+                    }
+
+                    let routes = routes.add_service(#route(self.clone()));
+                }
+            })
+            .collect();
 
         quote! {
             #[derive(Clone)]
@@ -265,25 +616,56 @@ impl ServiceDefinition {
                         .await
                         .expect("Unable to find an open port");
 
-                    Self(wiremock_grpc::GrpcServer::new(port)).start_internal().await
+                    Self::start_with(wiremock_grpc::GrpcServer::new(port)).await
                 }
 
                 pub async fn start(port: u16) -> Self {
-                    Self(wiremock_grpc::GrpcServer::new(port)).start_internal().await
+                    Self::start_with(wiremock_grpc::GrpcServer::new(port)).await
                 }
 
                 pub async fn start_with_addr(addr: ::std::net::SocketAddr) -> Self {
-                    Self(wiremock_grpc::GrpcServer::with_addr(addr)).start_internal().await
+                    Self::start_with(wiremock_grpc::GrpcServer::with_addr(addr)).await
+                }
+
+                /// Start the server without binding to a port, use `channel()` to connect to it.
+                pub async fn start_in_memory() -> Self {
+                    Self::start_with(wiremock_grpc::GrpcServer::in_memory()).await
+                }
+
+                /// Start the server on a Unix domain socket.
+                #[cfg(unix)]
+                pub async fn start_uds(path: impl Into<::std::path::PathBuf>) -> Self {
+                    Self::start_with(wiremock_grpc::GrpcServer::with_uds(path)).await
+                }
+
+                /// Start the server on a random port and its own runtime, for tests without one.
+                pub fn start_blocking() -> wiremock_grpc::BlockingServer<Self> {
+                    wiremock_grpc::BlockingServer::start(Self::start_default())
+                }
+
+                /// A handle on one server started for the whole process, isolated from the
+                /// handles of the other tests.
+                pub fn shared() -> wiremock_grpc::SharedServer {
+                    static SERVER: ::std::sync::OnceLock<wiremock_grpc::BlockingServer<#server_name>> =
+                        ::std::sync::OnceLock::new();
+                    let server = SERVER.get_or_init(|| {
+                        // on a thread of its own, outside of the runtime of the first test
+                        ::std::thread::spawn(Self::start_blocking)
+                            .join()
+                            .expect("Unable to start the shared mock server")
+                    });
+                    wiremock_grpc::SharedServer::new(server)
+                }
+
+                /// Start a server created with one of the [`GrpcServer`](wiremock_grpc::GrpcServer) constructors.
+                pub async fn start_with(server: wiremock_grpc::GrpcServer) -> Self {
+                    Self(server).start_internal().await
                 }
 
                 async fn start_internal(&mut self) -> Self {
-                    let address = self.address().clone();
-                    let thread = ::tokio::spawn(
-                        wiremock_grpc::tonic::transport::Server::builder()
-                            .add_service(self.clone())
-                            .serve(address),
-                    );
-                    self._start(thread).await;
+                    let routes = wiremock_grpc::tonic::service::Routes::new(self.clone());
+                    #(#routes)*
+                    self._serve(routes, &[#(#prefixes),*]).await;
                     self.to_owned()
                 }
             }
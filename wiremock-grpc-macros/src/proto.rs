@@ -0,0 +1,316 @@
+//! A minimal `.proto` reader, just enough to discover packages, services and rpc methods.
+//!
+//! Messages, enums, options and imports are skipped: the mock server only needs the
+//! fully qualified path of each rpc.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProtoFile {
+    pub(crate) package: String,
+    pub(crate) services: Vec<ProtoService>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProtoService {
+    pub(crate) name: String,
+    pub(crate) methods: Vec<ProtoMethod>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProtoMethod {
+    pub(crate) name: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Symbol(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "`{s}`"),
+            Token::Str(s) => write!(f, "\"{s}\""),
+            Token::Symbol(c) => write!(f, "`{c}`"),
+        }
+    }
+}
+
+pub(crate) fn parse(source: &str) -> Result<ProtoFile, ParseError> {
+    Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    }
+    .file()
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+        } else if ch == '/' {
+            chars.next();
+            match chars.next() {
+                Some('/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some('*') => {
+                    let mut prev = '\0';
+                    loop {
+                        match chars.next() {
+                            Some('/') if prev == '*' => break,
+                            Some(c) => prev = c,
+                            None => return Err(ParseError("unterminated block comment".into())),
+                        }
+                    }
+                }
+                _ => return Err(ParseError("unexpected `/`".into())),
+            }
+        } else if ch == '"' || ch == '\'' {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => {
+                        if let Some(c) = chars.next() {
+                            value.push(c);
+                        }
+                    }
+                    Some(c) if c == ch => break,
+                    Some(c) => value.push(c),
+                    None => return Err(ParseError("unterminated string literal".into())),
+                }
+            }
+            tokens.push(Token::Str(value));
+        } else if ch.is_alphanumeric() || ch == '_' || ch == '.' {
+            let mut value = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' || c == '.' {
+                    value.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Ident(value));
+        } else {
+            chars.next();
+            tokens.push(Token::Symbol(ch));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn file(mut self) -> Result<ProtoFile, ParseError> {
+        let mut file = ProtoFile {
+            package: String::new(),
+            services: Vec::new(),
+        };
+
+        while let Some(token) = self.next() {
+            match token {
+                Token::Ident(kw) if kw == "package" => {
+                    file.package = self.ident()?;
+                    self.symbol(';')?;
+                }
+                Token::Ident(kw) if kw == "service" => {
+                    let service = self.service()?;
+                    file.services.push(service);
+                }
+                Token::Ident(kw) if kw == "message" || kw == "enum" || kw == "extend" => {
+                    self.ident()?;
+                    self.skip_block()?;
+                }
+                Token::Symbol(';') => {}
+                // syntax, edition, import, option: all end with a semicolon.
+                Token::Ident(_) => self.skip_statement()?,
+                other => return Err(ParseError(format!("unexpected {other}"))),
+            }
+        }
+
+        Ok(file)
+    }
+
+    fn service(&mut self) -> Result<ProtoService, ParseError> {
+        let name = self.ident()?;
+        self.symbol('{')?;
+
+        let mut methods = Vec::new();
+        loop {
+            match self.next() {
+                Some(Token::Symbol('}')) => break,
+                Some(Token::Symbol(';')) => {}
+                Some(Token::Ident(kw)) if kw == "rpc" => methods.push(self.method()?),
+                Some(Token::Ident(_)) => self.skip_statement()?,
+                Some(other) => {
+                    return Err(ParseError(format!(
+                        "unexpected {other} in service `{name}`"
+                    )))
+                }
+                None => return Err(ParseError(format!("unterminated service `{name}`"))),
+            }
+        }
+
+        Ok(ProtoService { name, methods })
+    }
+
+    fn method(&mut self) -> Result<ProtoMethod, ParseError> {
+        let name = self.ident()?;
+        self.skip_method_type()?;
+        let returns = self.ident()?;
+        if returns != "returns" {
+            return Err(ParseError(format!(
+                "expected `returns` in rpc `{name}`, found `{returns}`"
+            )));
+        }
+        self.skip_method_type()?;
+
+        match self.next() {
+            Some(Token::Symbol(';')) => {}
+            Some(Token::Symbol('{')) => {
+                self.pos -= 1;
+                self.skip_block()?;
+            }
+            _ => {
+                return Err(ParseError(format!(
+                    "expected `;` or `{{` after rpc `{name}`"
+                )))
+            }
+        }
+
+        Ok(ProtoMethod { name })
+    }
+
+    /// Skips the `(stream Type)` of an rpc, the message type may be named `stream` too.
+    fn skip_method_type(&mut self) -> Result<(), ParseError> {
+        self.symbol('(')?;
+        self.ident()?;
+        if matches!(self.peek(), Some(Token::Ident(_))) {
+            self.ident()?;
+        }
+        self.symbol(')')
+    }
+
+    /// Skips a `{ ... }` block, including any nested blocks.
+    fn skip_block(&mut self) -> Result<(), ParseError> {
+        self.symbol('{')?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(Token::Symbol('{')) => depth += 1,
+                Some(Token::Symbol('}')) => depth -= 1,
+                Some(_) => {}
+                None => return Err(ParseError("unterminated block".into())),
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips everything up to and including the next `;`, stepping over option blocks.
+    fn skip_statement(&mut self) -> Result<(), ParseError> {
+        loop {
+            match self.peek() {
+                Some(Token::Symbol(';')) => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(Token::Symbol('{')) => self.skip_block()?,
+                Some(_) => self.pos += 1,
+                None => return Err(ParseError("expected `;`".into())),
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.next() {
+            Some(Token::Ident(s)) => Ok(s),
+            Some(other) => Err(ParseError(format!("expected identifier, found {other}"))),
+            None => Err(ParseError("expected identifier, found end of file".into())),
+        }
+    }
+
+    fn symbol(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.next() {
+            Some(Token::Symbol(c)) if c == expected => Ok(()),
+            Some(other) => Err(ParseError(format!("expected `{expected}`, found {other}"))),
+            None => Err(ParseError(format!(
+                "expected `{expected}`, found end of file"
+            ))),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+}
+
+#[test]
+fn test_parse_proto() {
+    let file = parse(
+        r#"
+        syntax = "proto3";
+        // comment with a service Fake { rpc Nope(A) returns (B); }
+        package hello.world;
+        import "google/protobuf/empty.proto";
+        option java_package = "io.hello";
+
+        /* block
+           comment */
+        message HelloRequest {
+            string name = 1;
+            message Nested { int32 id = 1 [deprecated = true]; }
+        }
+
+        service Greeter {
+            option deprecated = false;
+            rpc SayHello (HelloRequest) returns (HelloReply) {}
+            rpc Chat(stream HelloRequest) returns (stream .hello.world.HelloReply) {
+                option idempotency_level = NO_SIDE_EFFECTS;
+            }
+            rpc Stream(stream) returns (HelloReply);
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(file.package, "hello.world");
+    assert_eq!(file.services.len(), 1);
+    let methods = &file.services[0].methods;
+    assert_eq!(file.services[0].name, "Greeter");
+    assert_eq!(
+        methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(),
+        ["SayHello", "Chat", "Stream"]
+    );
+}
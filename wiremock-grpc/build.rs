@@ -1,6 +1,14 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_prost_build::compile_protos("proto/hello.proto")?;
-    tonic_prost_build::compile_protos("proto/extended.proto")?;
+    // the descriptor sets are loaded by the tests of the `json` and `reflection` features
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+    wiremock_grpc_build::configure()
+        .file_descriptor_set_path(out_dir.join("hello.bin"))
+        .compile_protos(&["proto/hello.proto"], &["proto"])?;
+    wiremock_grpc_build::compile_protos("proto/extended.proto")?;
+    wiremock_grpc_build::configure()
+        .file_descriptor_set_path(out_dir.join("multi.bin"))
+        .compile_protos(&["proto/multi.proto"], &["proto"])?;
+    wiremock_grpc_build::compile_protos("proto/streaming.proto")?;
 
     Ok(())
 }
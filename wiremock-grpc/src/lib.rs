@@ -58,14 +58,7 @@
 //!     );
 //!
 //!     // Connect your client and test
-//!     let channel = tonic::transport::Channel::from_shared(
-//!         format!("http://[::1]:{}", server.address().port())
-//!     )
-//!     .unwrap()
-//!     .connect()
-//!     .await
-//!     .unwrap();
-//!
+//!     let channel = server.channel().await;
 //!     let mut client = GreeterClient::new(channel);
 //!     let response = client
 //!         .say_hello(HelloRequest { name: "World".into() })
@@ -79,11 +72,50 @@
 //! ## Features
 //!
 //! - **Type-safe API**: Generate type-safe `path_*` methods for each RPC using [`generate_svc!`]
-//! - **Header Matching**: Match requests based on gRPC metadata/headers
+//! - **Wildcard Paths**: Match every rpc of a service with [`WhenBuilder::any_method_of`] or the
+//!   generated `any_{service}_method`, or the paths by prefix, glob or regular expression
+//! - **Header Matching**: Match requests based on gRPC metadata/headers: exact values, presence,
+//!   prefixes, regular expressions, multiple values or binary metadata
+//! - **Custom Matchers**: Any other check on the path, metadata or message of the requests with
+//!   [`WhenBuilder::matching`] and the [`Match`] trait
 //! - **Status Codes**: Return any gRPC status code
-//! - **Custom Bodies**: Return custom response bodies with closures
-//! - **Request Verification**: Track invocations and verify calls were made
+//! - **Custom Bodies**: Return custom response bodies with closures, called for every request
+//!   with `return_body_with`
+//! - **Request Verification**: Track invocations and verify calls were made, see every request
+//!   received with [`GrpcServer::journal`] and check their order with
+//!   [`GrpcServer::assert_called_in_order`]
+//! - **Partial Mocking**: Forward unmatched requests to a real server with
+//!   [`GrpcServer::proxy_unmatched_to`]
+//! - **Blocking API**: Start a server on its own runtime from plain `#[test]` functions with the
+//!   generated `start_blocking()`, see [`BlockingServer`]
+//! - **Shared Server**: One server for every test of a process with the generated `shared()`, each
+//!   test isolated in its own namespace, see [`SharedServer`]
 //! - **Flexible Binding**: Start servers on random ports, specific ports, or custom addresses
+//! - **Record and Replay**: Forward unmatched requests to a real server, record them with
+//!   [`GrpcServer::record_to`] and replay them later with [`GrpcServer::replay_from`]
+//! - **Transports**: Serve over TCP, in-memory, unix domain sockets or TLS (`tls` feature) and
+//!   connect with [`GrpcServer::channel`] or [`GrpcServer::client`]
+//! - **Fault Injection**: Reset the stream, close the connection or send `GOAWAY` instead of
+//!   responding with [`Then::fault`], to test reconnects and retries
+//! - **Chaos Mode**: Fail a fraction of the calls and add latency at random, reproducibly from a
+//!   seed, with [`GrpcServer::chaos`]
+//! - **Malformed Responses**: Send corrupted frames with `return_raw_frame` and the
+//!   [`raw_frame`] presets, to test the decoding errors of a client
+//! - **Compression**: Accept gzip (`gzip` feature, by default), deflate and zstd (`compression`
+//!   feature) compressed requests and compress the responses with `compress_with`, the encodings
+//!   used by the client being recorded in each [`RequestItem`](wiremock::grpc_server::RequestItem)
+//! - **JSON Bodies**: Write the messages as JSON with `return_json` and `body_json` (`json`
+//!   feature), encoded using the descriptors given to `GrpcServer::add_descriptors`
+//! - **Server Reflection**: Serve `grpc.reflection.v1` for tools like `grpcurl` (`reflection`
+//!   feature), see `GrpcServer::with_reflection`
+//! - **Health Checks**: Serve `grpc.health.v1` with a status controlled by the test (`health`
+//!   feature), see `GrpcServer::with_health` and `GrpcServer::set_health`
+//! - **gRPC-Web**: Accept gRPC-Web calls from browsers, CORS included (`web` feature), see
+//!   `GrpcServer::with_grpc_web`
+//! - **Stub Mappings**: Write rules as JSON documents with `Mapping` (`json` feature), or serve
+//!   them without writing any Rust using the `wiremock-grpc` binary (`wiremock-grpc-cli` crate)
+//! - **Admin API**: Configure a server running in another process over HTTP/JSON (`admin`
+//!   feature), see `GrpcServer::start_admin`
 //!
 //! ## Custom Server Name
 //!
@@ -102,6 +134,37 @@
 //! # }
 //! ```
 //!
+//! ## Typed Request and Response
+//!
+//! Declaring the message types of an rpc makes `path_*` return a [`TypedWhenBuilder`], which
+//! only accepts the matching reply type and can match on the request message:
+//!
+//! ```ignore
+//! generate_svc! {
+//!     package hello;
+//!     service Greeter {
+//!         SayHello(HelloRequest) -> HelloReply,
+//!     }
+//! }
+//!
+//! server.setup(
+//!     MockBuilder::when()
+//!         .path_say_hello()
+//!         .body_eq(HelloRequest { name: "World".into() })
+//!         .then()
+//!         .return_body(|| HelloReply { message: "Hello World".into() }),
+//! );
+//! ```
+//!
+//! ## Generating from a `.proto` File
+//!
+//! The macro can read the service definition from a proto file (relative to your `Cargo.toml`).
+//! All rpc methods are generated and a service missing from the file fails to compile:
+//!
+//! ```ignore
+//! wiremock_grpc::generate_svc!(proto = "proto/hello.proto", service = "Greeter");
+//! ```
+//!
 //! ## Header Matching
 //!
 //! Match requests based on gRPC metadata:
@@ -138,17 +201,32 @@
 //! ## Main Types
 //!
 //! - [`MockBuilder`] - Build mock behaviors with `when()` and `then()` pattern
-//! - [`WhenBuilder`] - Configure request matching (path, headers, etc.)
-//! - [`Then`] - Configure response behavior (status, body, headers)
+//! - [`WhenBuilder`] - Configure request matching (path, headers, body, etc.)
+//! - [`TypedWhenBuilder`] / [`TypedThenBuilder`] - Same, bound to the message types of an rpc
+//! - [`Then`] - Configure response behavior (status, body, headers, trailers)
 //! - [`GrpcServer`] - The underlying mock server (dereferenced by generated servers)
 
 pub mod wiremock;
 
+pub use wiremock::blocking::BlockingServer;
 pub use wiremock::builder::{MockBuilder, Mountable, Then, WhenBuilder};
+pub use wiremock::chaos::{ChaosConfig, ChaosDecision, LatencyDistribution};
+pub use wiremock::fault::Fault;
 pub use wiremock::grpc_server::GrpcServer;
+#[cfg(feature = "json")]
+pub use wiremock::mapping::{Mapping, MappingError};
+pub use wiremock::matching::{GrpcRequest, Match};
+pub use wiremock::raw_frame;
+pub use wiremock::shared::{SharedServer, NAMESPACE_HEADER};
 pub use wiremock::tonic_ext;
+pub use wiremock::typed::{TypedThenBuilder, TypedWhenBuilder};
 
 pub use wiremock_grpc_macros::generate_svc;
 
+#[cfg(feature = "health")]
+pub use tonic_health::ServingStatus;
+
 pub extern crate http_body;
+#[cfg(feature = "json")]
+pub extern crate prost_reflect;
 pub extern crate tonic;
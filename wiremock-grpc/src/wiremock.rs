@@ -1,5 +1,18 @@
+#[cfg(feature = "admin")]
+pub mod admin;
+pub mod blocking;
 pub mod builder;
+pub mod chaos;
 pub mod codegen;
+pub mod fault;
 pub mod grpc_server;
 pub mod invocations;
+#[cfg(feature = "json")]
+pub mod mapping;
+pub mod matching;
+pub mod proxy;
+pub mod raw_frame;
+pub mod shared;
 pub mod tonic_ext;
+pub mod transport;
+pub mod typed;
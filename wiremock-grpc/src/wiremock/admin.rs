@@ -0,0 +1,309 @@
+//! HTTP/JSON API configuring a running server from another process.
+//!
+//! | Method   | Path                     | Body             | Description                                   |
+//! |----------|--------------------------|------------------|-----------------------------------------------|
+//! | `GET`    | `/__admin/mappings`      |                  | The rules, with their id and invocations      |
+//! | `POST`   | `/__admin/mappings`      | [`Mapping`]      | Adds a rule, returns its id                   |
+//! | `DELETE` | `/__admin/mappings/{id}` |                  | Removes a rule                                |
+//! | `DELETE` | `/__admin/mappings`      |                  | Removes every rule                            |
+//! | `GET`    | `/__admin/requests`      |                  | The journal                                   |
+//! | `DELETE` | `/__admin/requests`      |                  | Clears the journal                            |
+//! | `POST`   | `/__admin/verify`        | [`Verification`] | Counts the requests matching a pattern        |
+//! | `POST`   | `/__admin/reset`         |                  | Removes every rule and clears the journal     |
+//!
+//! Errors are returned as `{ "error": "..." }`.
+
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use log::{info, warn};
+use prost_reflect::{DescriptorPool, DynamicMessage};
+use serde::{Deserialize, Serialize};
+use tonic::codegen::http;
+
+use crate::wiremock::{
+    grpc_server::{Journal, RequestItem, RuleItem},
+    mapping::{find_method, RequestPattern},
+    proxy::header_values,
+};
+use crate::{GrpcServer, Mapping, MappingError};
+
+impl GrpcServer {
+    /// Serve the admin API on `address`, converting the JSON messages with the descriptors
+    /// found in `pool`. Returns the address it listens on (eg. when started on port `0`).
+    ///
+    /// The rules added through the API are the rules of this server: they are listed by
+    /// [`rules_len`](Self::rules_len), verified when the server is dropped, and so on.
+    /// See the [`admin`](crate::wiremock::admin) module for the endpoints.
+    ///
+    /// The admin API stops when the last handle to the started server is dropped, or when
+    /// started again.
+    /// ```no_run
+    /// # async fn example(server: wiremock_grpc::GrpcServer, pool: prost_reflect::DescriptorPool) {
+    /// let admin = server.start_admin("127.0.0.1:8080".parse().unwrap(), pool).await.unwrap();
+    /// // curl -X POST http://127.0.0.1:8080/__admin/mappings -d @mapping.json
+    /// # }
+    /// ```
+    pub async fn start_admin(
+        &self,
+        address: SocketAddr,
+        pool: DescriptorPool,
+    ) -> std::io::Result<SocketAddr> {
+        let admin = Admin {
+            rules: self.rules.clone(),
+            journal: self.journal.clone(),
+            mappings: Arc::default(),
+            pool,
+        };
+
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        let address = listener.local_addr()?;
+        info!("Starting the admin API in {address}");
+
+        let task = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, admin.router()).await {
+                warn!("The admin API in {address} failed: {e}");
+            }
+        });
+        *self.admin.lock().unwrap() = Some(AdminTask(task));
+        Ok(address)
+    }
+}
+
+/// The task serving the admin API, stopped when dropped.
+#[derive(Debug)]
+pub(crate) struct AdminTask(tokio::task::JoinHandle<()>);
+
+impl Drop for AdminTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Shares the storage of the [`GrpcServer`], not the server itself: dropping it would verify
+/// the rules.
+#[derive(Clone)]
+struct Admin {
+    rules: Arc<RwLock<Vec<RuleItem>>>,
+    journal: Arc<RwLock<Journal>>,
+    /// The mappings added through the API, by rule id.
+    mappings: Arc<Mutex<BTreeMap<u64, Mapping>>>,
+    pool: DescriptorPool,
+}
+
+/// A rule, as listed by `GET /__admin/mappings`.
+#[derive(Debug, Serialize)]
+pub struct MappingEntry {
+    pub id: u64,
+    pub path: String,
+    /// Number of requests the rule matched.
+    pub invocations: u32,
+    /// The mapping the rule was created from, when added through the API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<Mapping>,
+}
+
+/// A request of the journal, as listed by `GET /__admin/requests`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub path: String,
+    pub headers: BTreeMap<String, Vec<String>>,
+    /// The request message as JSON, when the rpc is found in the descriptors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+    /// The encoded request message, base64 encoded.
+    pub raw_body: String,
+    pub proxied: bool,
+}
+
+/// The body of `POST /__admin/verify`: the requests matching `request` are counted, and must
+/// be exactly `count` of them (at least one when not set).
+///
+/// Answers `200 OK` when verified, `409 Conflict` otherwise, with the number of matching
+/// requests: `{ "count": 2 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Verification {
+    pub request: RequestPattern,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+struct Error(StatusCode, String);
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<MappingError> for Error {
+    fn from(e: MappingError) -> Self {
+        Self(StatusCode::BAD_REQUEST, e.to_string())
+    }
+}
+
+impl Admin {
+    fn router(self) -> Router {
+        Router::new()
+            .route(
+                "/__admin/mappings",
+                get(Self::list_mappings)
+                    .post(Self::add_mapping)
+                    .delete(Self::remove_mappings),
+            )
+            .route("/__admin/mappings/{id}", delete(Self::remove_mapping))
+            .route(
+                "/__admin/requests",
+                get(Self::requests).delete(Self::clear_requests),
+            )
+            .route("/__admin/verify", post(Self::verify))
+            .route("/__admin/reset", post(Self::reset))
+            .with_state(self)
+    }
+
+    async fn list_mappings(State(admin): State<Self>) -> Json<serde_json::Value> {
+        let mappings = admin.mappings.lock().unwrap();
+        let entries: Vec<_> = admin
+            .rules
+            .read()
+            .unwrap()
+            .iter()
+            .map(|item| MappingEntry {
+                id: item.id,
+                path: item.rule.path.clone(),
+                invocations: item.invocations_count,
+                mapping: mappings.get(&item.id).cloned(),
+            })
+            .collect();
+
+        Json(serde_json::json!({ "mappings": entries }))
+    }
+
+    async fn add_mapping(
+        State(admin): State<Self>,
+        Json(mapping): Json<Mapping>,
+    ) -> Result<(StatusCode, Json<serde_json::Value>), Error> {
+        let item = RuleItem::new(mapping.to_rule(&admin.pool)?);
+        let id = item.id;
+        info!("Adding mapping {id} for {}", item.rule.path);
+
+        admin.mappings.lock().unwrap().insert(id, mapping);
+        admin.rules.write().unwrap().push(item);
+
+        Ok((StatusCode::CREATED, Json(serde_json::json!({ "id": id }))))
+    }
+
+    async fn remove_mapping(
+        State(admin): State<Self>,
+        Path(id): Path<u64>,
+    ) -> Result<StatusCode, Error> {
+        admin.mappings.lock().unwrap().remove(&id);
+
+        let mut rules = admin.rules.write().unwrap();
+        let Some(index) = rules.iter().position(|item| item.id == id) else {
+            return Err(Error(StatusCode::NOT_FOUND, format!("no mapping {id}")));
+        };
+        rules.remove(index);
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    async fn remove_mappings(State(admin): State<Self>) -> StatusCode {
+        admin.mappings.lock().unwrap().clear();
+        admin.rules.write().unwrap().clear();
+        StatusCode::NO_CONTENT
+    }
+
+    async fn requests(State(admin): State<Self>) -> Json<serde_json::Value> {
+        let entries: Vec<_> = admin
+            .journal
+            .read()
+            .unwrap()
+            .iter()
+            .map(|request| admin.journal_entry(request))
+            .collect();
+
+        Json(serde_json::json!({ "requests": entries }))
+    }
+
+    async fn clear_requests(State(admin): State<Self>) -> StatusCode {
+        admin.journal.write().unwrap().clear();
+        StatusCode::NO_CONTENT
+    }
+
+    async fn verify(
+        State(admin): State<Self>,
+        Json(verification): Json<Verification>,
+    ) -> Result<(StatusCode, Json<serde_json::Value>), Error> {
+        let mapping = Mapping {
+            request: verification.request,
+            response: Default::default(),
+        };
+        let rule = mapping.to_rule(&admin.pool)?;
+
+        let count = admin
+            .journal
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|request| rule.matches(&to_request(request)))
+            .count();
+
+        let verified = match verification.count {
+            Some(expected) => count == expected,
+            None => count > 0,
+        };
+        let status = if verified {
+            StatusCode::OK
+        } else {
+            StatusCode::CONFLICT
+        };
+
+        Ok((status, Json(serde_json::json!({ "count": count }))))
+    }
+
+    async fn reset(State(admin): State<Self>) -> StatusCode {
+        admin.mappings.lock().unwrap().clear();
+        admin.rules.write().unwrap().clear();
+        admin.journal.write().unwrap().clear();
+        StatusCode::NO_CONTENT
+    }
+
+    fn journal_entry(&self, request: &RequestItem) -> JournalEntry {
+        let path = to_request(request).uri().path().to_string();
+        let body = find_method(&self.pool, &path)
+            .ok()
+            .and_then(|method| DynamicMessage::decode(method.input(), request.body.as_slice()).ok())
+            .and_then(|message| serde_json::to_value(message).ok());
+
+        JournalEntry {
+            path,
+            headers: header_values(&request.headers),
+            body,
+            raw_body: STANDARD.encode(&request.body),
+            proxied: request.proxied,
+        }
+    }
+}
+
+/// The request as the rules see it.
+fn to_request(item: &RequestItem) -> http::Request<Vec<u8>> {
+    let mut request = http::Request::new(item.body.clone());
+    *request.method_mut() = item.method.clone();
+    *request.uri_mut() = item.uri.parse().unwrap_or_default();
+    *request.headers_mut() = item.headers.clone();
+    request
+}
@@ -0,0 +1,80 @@
+//! Mock servers for tests without an async runtime, see [`BlockingServer`].
+
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+};
+
+use tokio::runtime::Runtime;
+use tonic::transport::Channel;
+
+use crate::GrpcServer;
+
+/// A mock server running on its own runtime, for plain `#[test]` functions, started with the
+/// generated `start_blocking()`.
+///
+/// It [`Deref`]s to the generated server, so the rules are set up and verified as usual with
+/// [`setup`](GrpcServer::setup), [`find`](GrpcServer::find),
+/// [`journal`](GrpcServer::journal)... The server and its runtime stop when it is dropped,
+/// panicking when a rule was never matched; call
+/// [`assert_all_matched`](GrpcServer::assert_all_matched) to verify the rules earlier.
+/// ```no_run
+/// # wiremock_grpc::generate_svc! { package hello; service Greeter { SayHello, } }
+/// # use wiremock_grpc::*;
+/// #[test]
+/// fn blocking() {
+///     let mut server = GreeterMockServer::start_blocking();
+///     server.setup(MockBuilder::when().path_say_hello().then().return_status(tonic::Code::Ok));
+///
+///     // call the server at `server.address()`
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BlockingServer<S> {
+    // dropped before the runtime it is served on
+    server: S,
+    runtime: Runtime,
+}
+
+impl<S: Deref<Target = GrpcServer>> BlockingServer<S> {
+    /// Starts a server with `start`, eg. `GreeterMockServer::start_default()`, on a new runtime.
+    ///
+    /// ## Panics
+    /// * When called from an async context.
+    pub fn start<F: Future<Output = S>>(start: F) -> Self {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("wiremock-grpc")
+            .enable_all()
+            .build()
+            .expect("Unable to start the runtime of the mock server");
+        let server = runtime.block_on(start);
+
+        Self { server, runtime }
+    }
+
+    /// Runs `future` to completion on the runtime of the server.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// A channel connected to the server, see [`GrpcServer::channel`]. It can be used from any
+    /// runtime.
+    pub fn channel(&self) -> Channel {
+        self.block_on(self.server.channel())
+    }
+}
+
+impl<S> Deref for BlockingServer<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.server
+    }
+}
+
+impl<S> DerefMut for BlockingServer<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.server
+    }
+}
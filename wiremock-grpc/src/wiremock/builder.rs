@@ -1,20 +1,82 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::wiremock::fault::Fault;
 use crate::wiremock::grpc_server::{GrpcServer, RuleItem};
+use crate::wiremock::matching::{GrpcRequest, Match, Matcher};
+use crate::wiremock::tonic_ext::grpc_timeout;
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use regex::Regex;
+use tonic::codec::CompressionEncoding;
 use tonic::codegen::http::header::IntoHeaderName;
+use tonic::codegen::http::header::{GetAll, HeaderName};
 use tonic::codegen::http::{request, HeaderMap, HeaderValue};
 
 pub trait Then {
     fn return_status(self, status: tonic::Code) -> Self;
 
+    /// Return the message built by `f`, called once when building the rule. See
+    /// [`return_body_with`](Self::return_body_with) for a message built for every request.
     fn return_body<T, F>(self, f: F) -> Self
     where
         F: Fn() -> T,
         T: prost::Message;
 
+    /// Return the message built by `f`, called again for every matched request so that the
+    /// messages can differ (timestamps, counters, random ids...).
+    fn return_body_with<T, F>(self, f: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: prost::Message;
+
     fn return_header<K, V>(self, key: K, value: V) -> Self
     where
         K: IntoHeaderName,
         V: TryInto<HeaderValue>,
         <V as TryInto<HeaderValue>>::Error: std::fmt::Debug;
+
+    /// Send a trailer (metadata sent after the response message) along with the status.
+    fn return_trailer<K, V>(self, key: K, value: V) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug;
+
+    /// Wait for `delay` before sending the response.
+    ///
+    /// When the delay exceeds the deadline of the request (`grpc-timeout`), the call fails with
+    /// `DeadlineExceeded` once the deadline is reached, like a real server.
+    fn with_delay(self, delay: Duration) -> Self;
+
+    /// Break the stream or the connection instead of responding, see [`Fault`].
+    fn fault(self, fault: Fault) -> Self;
+
+    /// Send `bytes` verbatim as the response body, followed by the status: unlike
+    /// [`return_body`](Self::return_body), the message is not framed. See the
+    /// [`raw_frame`](crate::raw_frame) presets to corrupt a response on purpose.
+    fn return_raw_frame(self, bytes: impl Into<Vec<u8>>) -> Self;
+
+    /// Return the message written as JSON, encoded when the rule is set up using the descriptors
+    /// given to [`GrpcServer::add_descriptors`].
+    ///
+    /// ## Panics
+    /// * When `json` is not valid JSON.
+    #[cfg(feature = "json")]
+    fn return_json(self, json: &str) -> Self;
+
+    /// Compress the response message with `encoding`, even when the client does not accept it
+    /// (see [`RequestItem::accept_encoding`](crate::wiremock::grpc_server::RequestItem::accept_encoding)).
+    #[cfg(feature = "gzip")]
+    fn compress_with(self, encoding: CompressionEncoding) -> Self;
 }
 
 pub trait Mountable {
@@ -24,17 +86,243 @@ pub trait Mountable {
 /// Builder pattern to set up a mock response for a given request.
 #[derive(Debug, Clone)]
 pub struct MockBuilder {
+    /// Identifies the rule, shared by its clones and new for every builder returned by a [`Then`]
+    /// method, see [`GrpcServer::find`].
+    pub(crate) id: u64,
     pub(crate) path: String,
+    pub(crate) path_matcher: PathMatcher,
     pub(crate) status_code: Option<tonic::Code>,
-    pub(crate) result: Option<Vec<u8>>,
+    pub(crate) result: Option<ResponseBody>,
     pub(crate) request_headers: HeaderMap,
+    pub(crate) request_header_matchers: Vec<(HeaderName, HeaderMatcher)>,
+    /// See [`WhenBuilder::matching`].
+    pub(crate) request_matchers: Vec<Matcher>,
+    pub(crate) request_body: Option<BodyMatcher>,
+    /// The requests must have a `grpc-timeout` at most this long.
+    pub(crate) deadline_at_most: Option<Duration>,
     pub(crate) response_headers: HeaderMap,
+    pub(crate) response_trailers: HeaderMap,
+    pub(crate) status_message: Option<String>,
+    pub(crate) delay: Option<Duration>,
+    pub(crate) compression: Option<CompressionEncoding>,
+    pub(crate) fault: Option<Fault>,
+    pub(crate) raw_frame: Option<Vec<u8>>,
+    /// Encoded into `result` when the rule is set up.
+    #[cfg(feature = "json")]
+    pub(crate) json_result: Option<serde_json::Value>,
+}
+
+/// The response message of a rule, encoded for every matched request.
+#[derive(Clone)]
+pub(crate) struct ResponseBody(Arc<dyn Fn() -> Vec<u8> + Send + Sync>);
+
+impl ResponseBody {
+    /// Always the same encoded message.
+    pub(crate) fn fixed(message: Vec<u8>) -> Self {
+        Self(Arc::new(move || message.clone()))
+    }
+
+    fn generated<T, F>(f: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: prost::Message,
+    {
+        Self(Arc::new(move || f().encode_to_vec()))
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        (self.0)()
+    }
+}
+
+impl std::fmt::Debug for ResponseBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ResponseBody(..)")
+    }
+}
+
+/// Identifies a new rule.
+fn next_rule_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// How the (first) request message is compared against a rule.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum BodyMatcher {
+    /// The request message must be encoded to exactly these bytes.
+    Exact(Vec<u8>),
+    /// Every top-level field encoded in these bytes must be present in the request
+    /// message with the same value. Other fields are ignored.
+    Partial(Vec<u8>),
+    /// A message written as JSON, encoded into one of the above when the rule is set up.
+    #[cfg(feature = "json")]
+    Json {
+        json: serde_json::Value,
+        partial: bool,
+    },
+}
+
+impl BodyMatcher {
+    fn matches(&self, body: &[u8]) -> bool {
+        match self {
+            BodyMatcher::Exact(expected) => expected == body,
+            BodyMatcher::Partial(expected) => {
+                let (Some(expected), Some(mut actual)) = (wire_fields(expected), wire_fields(body))
+                else {
+                    return false;
+                };
+
+                expected.iter().all(|field| {
+                    if let Some(i) = actual.iter().position(|f| f == field) {
+                        actual.swap_remove(i);
+                        true
+                    } else {
+                        false
+                    }
+                })
+            }
+            #[cfg(feature = "json")]
+            BodyMatcher::Json { .. } => false,
+        }
+    }
+}
+
+/// How the path of the request is compared against the `path` of a rule.
+#[derive(Debug, Clone, Default)]
+pub(crate) enum PathMatcher {
+    #[default]
+    Exact,
+    /// The path starts with the `path` of the rule, eg. `/hello.Greeter/`.
+    Prefix,
+    /// The path matches, compiled from the glob or regular expression `path` of the rule.
+    Regex(Regex),
+}
+
+impl PathMatcher {
+    fn matches(&self, pattern: &str, path: &str) -> bool {
+        match self {
+            PathMatcher::Exact => pattern == path,
+            PathMatcher::Prefix => path.starts_with(pattern),
+            PathMatcher::Regex(regex) => regex.is_match(path),
+        }
+    }
+}
+
+/// `*` matches any characters but `/`, `**` any characters and `?` a single character.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.next_if_eq(&'*').is_some() => regex.push_str(".*"),
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+/// How a header (metadata) of the request is compared against a rule, the ones set with
+/// [`WhenBuilder::header`] must be exactly equal.
+#[derive(Debug, Clone)]
+pub(crate) enum HeaderMatcher {
+    Present,
+    Absent,
+    /// One of the values starts with this prefix.
+    Prefix(String),
+    /// One of the values is equal, ignoring the ASCII case.
+    EqIgnoreCase(String),
+    /// One of the values matches.
+    Regex(Regex),
+    /// Every one of these values is present, along with any other.
+    Values(Vec<HeaderValue>),
+    /// One of the values of a `-bin` header decodes to these bytes.
+    Binary(Vec<u8>),
+}
+
+impl HeaderMatcher {
+    fn matches(&self, values: GetAll<'_, HeaderValue>) -> bool {
+        let mut texts = values.iter().filter_map(|value| value.to_str().ok());
+        match self {
+            HeaderMatcher::Present => values.iter().next().is_some(),
+            HeaderMatcher::Absent => values.iter().next().is_none(),
+            HeaderMatcher::Prefix(prefix) => texts.any(|value| value.starts_with(prefix.as_str())),
+            HeaderMatcher::EqIgnoreCase(expected) => {
+                texts.any(|value| value.eq_ignore_ascii_case(expected))
+            }
+            HeaderMatcher::Regex(regex) => texts.any(|value| regex.is_match(value)),
+            HeaderMatcher::Values(expected) => expected
+                .iter()
+                .all(|e| values.iter().any(|value| value == e)),
+            HeaderMatcher::Binary(expected) => values.iter().any(|value| {
+                BINARY_METADATA
+                    .decode(value.as_bytes())
+                    .is_ok_and(|decoded| &decoded == expected)
+            }),
+        }
+    }
+}
+
+/// Binary metadata is base64 encoded, with or without padding.
+const BINARY_METADATA: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Splits an encoded protobuf message into its top-level fields (key and raw value).
+///
+/// Returns [`None`] when the bytes are not a valid message.
+fn wire_fields(mut buf: &[u8]) -> Option<Vec<(u64, &[u8])>> {
+    fn varint(buf: &mut &[u8]) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = buf.split_first()?;
+            *buf = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    let mut fields = Vec::new();
+    while !buf.is_empty() {
+        let key = varint(&mut buf)?;
+        let start = buf;
+        let len = match key & 0x7 {
+            0 => {
+                varint(&mut buf)?;
+                start.len() - buf.len()
+            }
+            1 => 8,
+            2 => usize::try_from(varint(&mut buf)?).ok()?,
+            5 => 4,
+            // groups are not supported
+            _ => return None,
+        };
+        if buf.len() < len {
+            return None;
+        }
+        let value = &start[..start.len() - buf.len() + len];
+        buf = &buf[len..];
+        fields.push((key, value));
+    }
+    Some(fields)
 }
 
 #[derive(Clone)]
 pub struct WhenBuilder {
     path: Option<String>,
+    path_matcher: PathMatcher,
     headers: HeaderMap,
+    header_matchers: Vec<(HeaderName, HeaderMatcher)>,
+    matchers: Vec<Matcher>,
+    body: Option<BodyMatcher>,
+    deadline_at_most: Option<Duration>,
 }
 impl WhenBuilder {
     #[deprecated(
@@ -44,7 +332,110 @@ impl WhenBuilder {
     pub fn path(&self, p: &str) -> Self {
         Self {
             path: Some(p.into()),
-            headers: self.headers.clone(),
+            path_matcher: PathMatcher::Exact,
+            ..self.clone()
+        }
+    }
+
+    /// Match the requests to any rpc of the service served by `S`, eg. a generated
+    /// `GreeterMockServer` (only its first service when it serves several).
+    pub fn any_method_of<S: tonic::server::NamedService>(&self) -> Self {
+        self.path_prefix(&format!("/{}/", S::NAME))
+    }
+
+    /// Match the requests whose path starts with `prefix`, eg. `/hello.Greeter/` for every
+    /// rpc of a service.
+    pub fn path_prefix(&self, prefix: &str) -> Self {
+        Self {
+            path: Some(prefix.into()),
+            path_matcher: PathMatcher::Prefix,
+            ..self.clone()
+        }
+    }
+
+    /// Match the requests whose path matches the glob `pattern`, where `*` stands for any
+    /// characters but `/`, `**` for any characters and `?` for a single one,
+    /// eg. `/hello.*/Say*`.
+    pub fn path_glob(&self, pattern: &str) -> Self {
+        let regex = Regex::new(&glob_to_regex(pattern)).unwrap();
+        Self {
+            path: Some(pattern.into()),
+            path_matcher: PathMatcher::Regex(regex),
+            ..self.clone()
+        }
+    }
+
+    /// Match the requests whose path matches the regular expression `pattern` (anywhere in the
+    /// path unless anchored with `^` and `$`).
+    ///
+    /// ## Panics
+    /// * When `pattern` is not a valid regular expression.
+    pub fn path_regex(&self, pattern: &str) -> Self {
+        let regex = Regex::new(pattern)
+            .unwrap_or_else(|e| panic!("Invalid regular expression `{pattern}`: {e}"));
+        Self {
+            path: Some(pattern.into()),
+            path_matcher: PathMatcher::Regex(regex),
+            ..self.clone()
+        }
+    }
+
+    /// Match requests whose message is exactly equal to `message`.
+    pub fn body_eq<T: prost::Message>(self, message: T) -> Self {
+        Self {
+            body: Some(BodyMatcher::Exact(message.encode_to_vec())),
+            ..self
+        }
+    }
+
+    /// Match requests containing every field set in `message`.
+    ///
+    /// Only the top-level fields that are set (ie. not the default value) are compared,
+    /// any other field in the request is ignored.
+    pub fn body_contains<T: prost::Message>(self, message: T) -> Self {
+        Self {
+            body: Some(BodyMatcher::Partial(message.encode_to_vec())),
+            ..self
+        }
+    }
+
+    /// Same as [`body_eq`](Self::body_eq), with the message written as JSON. It is encoded
+    /// when the rule is set up, using the descriptors given to [`GrpcServer::add_descriptors`].
+    ///
+    /// ## Panics
+    /// * When `json` is not valid JSON.
+    #[cfg(feature = "json")]
+    pub fn body_json(self, json: &str) -> Self {
+        Self {
+            body: Some(BodyMatcher::Json {
+                json: parse_json(json),
+                partial: false,
+            }),
+            ..self
+        }
+    }
+
+    /// Same as [`body_contains`](Self::body_contains), with the message written as JSON,
+    /// see [`body_json`](Self::body_json).
+    #[cfg(feature = "json")]
+    pub fn body_json_contains(self, json: &str) -> Self {
+        Self {
+            body: Some(BodyMatcher::Json {
+                json: parse_json(json),
+                partial: true,
+            }),
+            ..self
+        }
+    }
+
+    /// Match requests with a deadline (`grpc-timeout`) of at most `timeout`, eg. to check that
+    /// a client propagates the deadline of its own request.
+    ///
+    /// Requests without a deadline do not match.
+    pub fn deadline_at_most(self, timeout: Duration) -> Self {
+        Self {
+            deadline_at_most: Some(timeout),
+            ..self
         }
     }
 
@@ -58,14 +449,102 @@ impl WhenBuilder {
         self
     }
 
+    /// Match requests for which `matcher` returns `true`, along with the other conditions.
+    /// Can be called several times, every matcher must match.
+    pub fn matching(mut self, matcher: impl Match) -> Self {
+        self.matchers.push(Matcher(Arc::new(matcher)));
+        self
+    }
+
+    /// Match requests having the header `key`, whatever its value.
+    pub fn header_exists<K: IntoHeaderName>(self, key: K) -> Self {
+        self.header_matcher(key, HeaderMatcher::Present)
+    }
+
+    /// Match requests without the header `key`.
+    pub fn header_missing<K: IntoHeaderName>(self, key: K) -> Self {
+        self.header_matcher(key, HeaderMatcher::Absent)
+    }
+
+    /// Match requests with a value of the header `key` starting with `prefix`.
+    pub fn header_prefix<K: IntoHeaderName>(self, key: K, prefix: &str) -> Self {
+        self.header_matcher(key, HeaderMatcher::Prefix(prefix.into()))
+    }
+
+    /// Match requests with a value of the header `key` equal to `value`, ignoring the ASCII case.
+    pub fn header_eq_ignore_case<K: IntoHeaderName>(self, key: K, value: &str) -> Self {
+        self.header_matcher(key, HeaderMatcher::EqIgnoreCase(value.into()))
+    }
+
+    /// Match requests with a value of the header `key` matching the regular expression
+    /// `pattern` (anywhere in the value unless anchored with `^` and `$`).
+    ///
+    /// ## Panics
+    /// * When `pattern` is not a valid regular expression.
+    pub fn header_regex<K: IntoHeaderName>(self, key: K, pattern: &str) -> Self {
+        let regex = Regex::new(pattern)
+            .unwrap_or_else(|e| panic!("Invalid regular expression `{pattern}`: {e}"));
+        self.header_matcher(key, HeaderMatcher::Regex(regex))
+    }
+
+    /// Match requests having every one of `values` for the header `key`, sent several times.
+    /// Other values are ignored.
+    pub fn header_values<K, V>(self, key: K, values: impl IntoIterator<Item = V>) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        let values = values.into_iter().map(|v| v.try_into().unwrap()).collect();
+        self.header_matcher(key, HeaderMatcher::Values(values))
+    }
+
+    /// Match requests with the binary metadata `key` (ending with `-bin`) decoding to `value`.
+    ///
+    /// ## Panics
+    /// * When `key` does not end with `-bin`.
+    pub fn header_bin<K: IntoHeaderName>(self, key: K, value: impl Into<Vec<u8>>) -> Self {
+        self.header_matcher(key, HeaderMatcher::Binary(value.into()))
+    }
+
+    fn header_matcher<K: IntoHeaderName>(mut self, key: K, matcher: HeaderMatcher) -> Self {
+        // `IntoHeaderName` is sealed, a map gives the name back
+        let mut map = HeaderMap::new();
+        map.insert(key, HeaderValue::from_static(""));
+        let name = map.keys().next().unwrap().clone();
+        if matches!(matcher, HeaderMatcher::Binary(_)) {
+            assert!(
+                name.as_str().ends_with("-bin"),
+                "Binary metadata must end with `-bin`, not `{name}`"
+            );
+        }
+
+        self.header_matchers.push((name, matcher));
+        self
+    }
+
     pub fn then(&self) -> ThenBuilder {
         self.validate();
         ThenBuilder {
+            id: next_rule_id(),
             path: self.path.clone().unwrap(),
+            path_matcher: self.path_matcher.clone(),
             status_code: None,
             result: None,
             request_headers: self.headers.clone(),
+            request_header_matchers: self.header_matchers.clone(),
+            request_matchers: self.matchers.clone(),
+            request_body: self.body.clone(),
+            deadline_at_most: self.deadline_at_most,
             response_headers: HeaderMap::new(),
+            response_trailers: HeaderMap::new(),
+            status_message: None,
+            delay: None,
+            compression: None,
+            fault: None,
+            raw_frame: None,
+            #[cfg(feature = "json")]
+            json_result: None,
         }
     }
 
@@ -78,36 +557,70 @@ impl WhenBuilder {
 
 #[derive(Clone)]
 pub struct ThenBuilder {
+    pub(crate) id: u64,
     pub(crate) path: String,
+    pub(crate) path_matcher: PathMatcher,
     pub(crate) status_code: Option<tonic::Code>,
-    pub(crate) result: Option<Vec<u8>>,
+    pub(crate) result: Option<ResponseBody>,
     pub(crate) request_headers: HeaderMap,
+    pub(crate) request_header_matchers: Vec<(HeaderName, HeaderMatcher)>,
+    /// See [`WhenBuilder::matching`].
+    pub(crate) request_matchers: Vec<Matcher>,
+    pub(crate) request_body: Option<BodyMatcher>,
+    /// The requests must have a `grpc-timeout` at most this long.
+    pub(crate) deadline_at_most: Option<Duration>,
     pub(crate) response_headers: HeaderMap,
+    pub(crate) response_trailers: HeaderMap,
+    pub(crate) status_message: Option<String>,
+    pub(crate) delay: Option<Duration>,
+    pub(crate) compression: Option<CompressionEncoding>,
+    pub(crate) fault: Option<Fault>,
+    pub(crate) raw_frame: Option<Vec<u8>>,
+    /// Encoded into `result` when the rule is set up.
+    #[cfg(feature = "json")]
+    pub(crate) json_result: Option<serde_json::Value>,
 }
 
 impl MockBuilder {
     pub fn given(path: &str) -> Self {
         Self {
+            id: next_rule_id(),
             path: path.into(),
+            path_matcher: PathMatcher::Exact,
             result: None,
             status_code: None,
             request_headers: HeaderMap::new(),
+            request_header_matchers: Vec::new(),
+            request_matchers: Vec::new(),
+            request_body: None,
+            deadline_at_most: None,
             response_headers: HeaderMap::new(),
+            response_trailers: HeaderMap::new(),
+            status_message: None,
+            delay: None,
+            compression: None,
+            fault: None,
+            raw_frame: None,
+            #[cfg(feature = "json")]
+            json_result: None,
         }
     }
 
     pub fn when() -> WhenBuilder {
         WhenBuilder {
             path: None,
+            path_matcher: PathMatcher::Exact,
             headers: HeaderMap::new(),
+            header_matchers: Vec::new(),
+            matchers: Vec::new(),
+            body: None,
+            deadline_at_most: None,
         }
     }
 
-    pub(crate) fn matches<B: http_body::Body + Send + 'static>(
-        &self,
-        req: &request::Request<B>,
-    ) -> bool {
-        if self.path != req.uri().path() {
+    /// `req` carries the first decoded request message as its body.
+    pub(crate) fn matches(&self, req: &request::Request<Vec<u8>>) -> bool {
+        if !self.path_matcher.matches(&self.path, req.uri().path()) {
             return false;
         }
 
@@ -124,21 +637,63 @@ impl MockBuilder {
             }
         }
 
+        for (key, matcher) in &self.request_header_matchers {
+            if !matcher.matches(req.headers().get_all(key)) {
+                return false;
+            }
+        }
+
+        if let Some(body) = &self.request_body {
+            if !body.matches(req.body()) {
+                return false;
+            }
+        }
+
+        let request = GrpcRequest::new(req);
+        if !self.request_matchers.iter().all(|m| m.0.matches(&request)) {
+            return false;
+        }
+
+        if let Some(at_most) = self.deadline_at_most {
+            match grpc_timeout(req.headers()) {
+                Some(deadline) if deadline <= at_most => {}
+                _ => return false,
+            }
+        }
+
         true
     }
 }
 
 impl Mountable for MockBuilder {
     fn mount(self, s: &mut GrpcServer) {
-        if self.status_code.is_none() && self.result.is_none() {
-            panic!("Must set the status code or body before attempting to mount the rule.");
+        s.add_rule(self);
+    }
+}
+
+impl MockBuilder {
+    /// ## Panics
+    /// * When the rule has nothing to respond with.
+    pub(crate) fn into_rule_item(self) -> RuleItem {
+        if self.status_code.is_none()
+            && self.result.is_none()
+            && self.fault.is_none()
+            && self.raw_frame.is_none()
+        {
+            panic!("Must set the status code, body or fault before attempting to mount the rule.");
         }
 
-        s.rules.write().unwrap().push(RuleItem {
-            invocations_count: 0,
-            invocations: Vec::default(),
-            rule: self,
-        });
+        RuleItem::new(self)
+    }
+}
+
+impl MockBuilder {
+    /// A new rule, built from this one by a [`Then`] method.
+    fn derived(self) -> Self {
+        Self {
+            id: next_rule_id(),
+            ..self
+        }
     }
 }
 
@@ -146,7 +701,7 @@ impl Then for MockBuilder {
     fn return_status(self, status: tonic::Code) -> Self {
         Self {
             status_code: Some(status),
-            ..self
+            ..self.derived()
         }
     }
 
@@ -155,16 +710,20 @@ impl Then for MockBuilder {
         F: Fn() -> T,
         T: prost::Message,
     {
-        let result = f();
-        let mut buf = prost::bytes::BytesMut::new();
-        result
-            .encode(&mut buf)
-            .expect("Unable to encode the message");
-        let result = buf.to_vec();
+        Self {
+            result: Some(ResponseBody::fixed(f().encode_to_vec())),
+            ..self.derived()
+        }
+    }
 
+    fn return_body_with<T, F>(self, f: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: prost::Message,
+    {
         Self {
-            result: Some(result),
-            ..self
+            result: Some(ResponseBody::generated(f)),
+            ..self.derived()
         }
     }
 
@@ -174,16 +733,76 @@ impl Then for MockBuilder {
         V: TryInto<HeaderValue>,
         <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
     {
+        self = self.derived();
         self.response_headers.insert(key, value.try_into().unwrap());
         self
     }
+
+    fn return_trailer<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self = self.derived();
+        self.response_trailers
+            .insert(key, value.try_into().unwrap());
+        self
+    }
+
+    fn with_delay(self, delay: Duration) -> Self {
+        Self {
+            delay: Some(delay),
+            ..self.derived()
+        }
+    }
+
+    fn fault(self, fault: Fault) -> Self {
+        Self {
+            fault: Some(fault),
+            ..self.derived()
+        }
+    }
+
+    fn return_raw_frame(self, bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            raw_frame: Some(bytes.into()),
+            ..self.derived()
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn return_json(self, json: &str) -> Self {
+        Self {
+            json_result: Some(parse_json(json)),
+            ..self.derived()
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    fn compress_with(self, encoding: CompressionEncoding) -> Self {
+        Self {
+            compression: Some(encoding),
+            ..self.derived()
+        }
+    }
+}
+
+impl ThenBuilder {
+    /// A new rule, built from this one by a [`Then`] method.
+    fn derived(self) -> Self {
+        Self {
+            id: next_rule_id(),
+            ..self
+        }
+    }
 }
 
 impl Then for ThenBuilder {
     fn return_status(self, status: tonic::Code) -> Self {
         Self {
             status_code: Some(status),
-            ..self
+            ..self.derived()
         }
     }
 
@@ -192,16 +811,20 @@ impl Then for ThenBuilder {
         F: Fn() -> T,
         T: prost::Message,
     {
-        let result = f();
-        let mut buf = prost::bytes::BytesMut::new();
-        result
-            .encode(&mut buf)
-            .expect("Unable to encode the message");
-        let result = buf.to_vec();
+        Self {
+            result: Some(ResponseBody::fixed(f().encode_to_vec())),
+            ..self.derived()
+        }
+    }
 
+    fn return_body_with<T, F>(self, f: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+        T: prost::Message,
+    {
         Self {
-            result: Some(result),
-            ..self
+            result: Some(ResponseBody::generated(f)),
+            ..self.derived()
         }
     }
 
@@ -211,20 +834,84 @@ impl Then for ThenBuilder {
         V: TryInto<HeaderValue>,
         <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
     {
+        self = self.derived();
         self.response_headers.insert(key, value.try_into().unwrap());
         self
     }
+
+    fn return_trailer<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self = self.derived();
+        self.response_trailers
+            .insert(key, value.try_into().unwrap());
+        self
+    }
+
+    fn with_delay(self, delay: Duration) -> Self {
+        Self {
+            delay: Some(delay),
+            ..self.derived()
+        }
+    }
+
+    fn fault(self, fault: Fault) -> Self {
+        Self {
+            fault: Some(fault),
+            ..self.derived()
+        }
+    }
+
+    fn return_raw_frame(self, bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            raw_frame: Some(bytes.into()),
+            ..self.derived()
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn return_json(self, json: &str) -> Self {
+        Self {
+            json_result: Some(parse_json(json)),
+            ..self.derived()
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    fn compress_with(self, encoding: CompressionEncoding) -> Self {
+        Self {
+            compression: Some(encoding),
+            ..self.derived()
+        }
+    }
 }
 
 #[allow(clippy::from_over_into)]
 impl Into<MockBuilder> for ThenBuilder {
     fn into(self) -> MockBuilder {
         MockBuilder {
+            id: self.id,
             path: self.path,
+            path_matcher: self.path_matcher,
             status_code: self.status_code,
             result: self.result,
             request_headers: self.request_headers,
+            request_header_matchers: self.request_header_matchers,
+            request_matchers: self.request_matchers,
+            request_body: self.request_body,
+            deadline_at_most: self.deadline_at_most,
             response_headers: self.response_headers,
+            response_trailers: self.response_trailers,
+            status_message: self.status_message,
+            delay: self.delay,
+            compression: self.compression,
+            fault: self.fault,
+            raw_frame: self.raw_frame,
+            #[cfg(feature = "json")]
+            json_result: self.json_result,
         }
     }
 }
@@ -235,3 +922,8 @@ impl Mountable for ThenBuilder {
         rb.mount(s);
     }
 }
+
+#[cfg(feature = "json")]
+fn parse_json(json: &str) -> serde_json::Value {
+    serde_json::from_str(json).expect("Invalid JSON")
+}
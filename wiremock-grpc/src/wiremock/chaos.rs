@@ -0,0 +1,131 @@
+//! Random failures and latencies applied on top of the rules, see [`GrpcServer::chaos`].
+
+use std::time::Duration;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tonic::Code;
+
+use crate::GrpcServer;
+
+/// How the server fails at random, see [`GrpcServer::chaos`].
+/// ```no_run
+/// # use std::time::Duration;
+/// # use wiremock_grpc::*;
+/// let config = ChaosConfig {
+///     error_rate: 0.1,
+///     codes: vec![tonic::Code::Unavailable, tonic::Code::ResourceExhausted],
+///     latency_distribution: LatencyDistribution::Uniform {
+///         min: Duration::from_millis(10),
+///         max: Duration::from_millis(50),
+///     },
+///     seed: 42,
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChaosConfig {
+    /// Fraction of the matched requests failing, between `0.0` (none) and `1.0` (all).
+    pub error_rate: f64,
+    /// The codes of the failures, picked at random. [`Code::Unavailable`] when empty.
+    pub codes: Vec<Code>,
+    /// Latency added to every matched request, on top of the delay of the rule.
+    pub latency_distribution: LatencyDistribution,
+    /// The same seed makes the same decisions for the same sequence of requests.
+    pub seed: u64,
+}
+
+/// The latency added by [`ChaosConfig`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LatencyDistribution {
+    #[default]
+    None,
+    Fixed(Duration),
+    /// Any latency between `min` and `max`, equally likely.
+    Uniform {
+        min: Duration,
+        max: Duration,
+    },
+    /// Mostly short latencies with a long tail, averaging `mean`.
+    Exponential {
+        mean: Duration,
+    },
+}
+
+/// What the chaos mode did to a request, see [`RequestItem::chaos`](crate::wiremock::grpc_server::RequestItem::chaos).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChaosDecision {
+    /// Added to the delay of the rule.
+    pub latency: Duration,
+    /// The code the request failed with instead of the response of the rule, if any.
+    pub code: Option<Code>,
+}
+
+/// The chaos mode of a server, with the state of its random generator.
+#[derive(Debug)]
+pub(crate) struct Chaos {
+    config: ChaosConfig,
+    rng: StdRng,
+}
+
+impl Chaos {
+    fn new(config: ChaosConfig) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(config.seed),
+            config,
+        }
+    }
+
+    pub(crate) fn decide(&mut self) -> ChaosDecision {
+        let latency = match self.config.latency_distribution {
+            LatencyDistribution::None => Duration::ZERO,
+            LatencyDistribution::Fixed(latency) => latency,
+            LatencyDistribution::Uniform { min, max } if min < max => self.rng.gen_range(min..=max),
+            LatencyDistribution::Uniform { min, .. } => min,
+            LatencyDistribution::Exponential { mean } => {
+                // inverse of the cumulative distribution function, the longest latencies of a
+                // huge mean saturate
+                let uniform: f64 = self.rng.gen();
+                Duration::try_from_secs_f64(mean.as_secs_f64() * -(1.0 - uniform).ln())
+                    .unwrap_or(Duration::MAX)
+            }
+        };
+
+        let failed = self.rng.gen_bool(self.config.error_rate.clamp(0.0, 1.0));
+        let code = failed.then(|| match self.config.codes.as_slice() {
+            [] => Code::Unavailable,
+            codes => codes[self.rng.gen_range(0..codes.len())],
+        });
+
+        ChaosDecision { latency, code }
+    }
+}
+
+impl GrpcServer {
+    /// Fail a fraction of the matched requests and add latency to them, at random but
+    /// reproducibly: the decisions only depend on the seed and on the order of the requests.
+    ///
+    /// Each decision is recorded in the journal, see
+    /// [`RequestItem::chaos`](crate::wiremock::grpc_server::RequestItem::chaos). Calling it
+    /// again restarts from the new seed, [`ChaosConfig::default`] disables the chaos mode.
+    /// ```no_run
+    /// # use wiremock_grpc::*;
+    /// # fn example(server: GrpcServer) {
+    /// server.chaos(ChaosConfig {
+    ///     error_rate: 0.05,
+    ///     seed: 42,
+    ///     ..Default::default()
+    /// });
+    /// # }
+    /// ```
+    ///
+    /// ## Panics
+    /// * When `error_rate` is NaN or infinite.
+    pub fn chaos(&self, config: ChaosConfig) {
+        assert!(
+            config.error_rate.is_finite(),
+            "Invalid chaos error rate {}, expected a value between 0.0 and 1.0",
+            config.error_rate
+        );
+        *self.chaos.lock().unwrap() =
+            (config != ChaosConfig::default()).then(|| Chaos::new(config));
+    }
+}
@@ -138,3 +138,29 @@ macro_rules! generate {
         }
     };
 }
+
+/// Include the mock servers generated by `wiremock-grpc-build` for the given package.
+///
+/// Like [`tonic::include_proto!`], this must be used in the module that holds the tonic code of
+/// the same package so that the message types resolve.
+///
+/// # Example
+/// ```no_run
+/// mod hello {
+///     tonic::include_proto!("hello");
+///     wiremock_grpc::include_mock!("hello");
+/// }
+///
+/// # async fn example() {
+/// let mut server = hello::GreeterMockServer::start_default().await;
+/// # }
+/// ```
+#[macro_export]
+macro_rules! include_mock {
+    ($package: tt) => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            concat!("/", $package, ".wiremock.rs")
+        ));
+    };
+}
@@ -0,0 +1,278 @@
+//! Transport-level faults, see [`Then::fault`](crate::Then::fault).
+//!
+//! Every connection accepted by a server created
+//! [`with_faults`](crate::GrpcServer::with_faults) is wrapped in a [`FaultyIo`], the rules reach
+//! it through the [`Connection`] found in the request extensions.
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{ready, Context, Poll, Waker},
+};
+
+use http_body_util::StreamBody;
+use log::warn;
+use prost::bytes::Bytes;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tonic::{
+    codegen::{
+        http::{self, HeaderValue},
+        tokio_stream,
+    },
+    transport::server::Connected,
+};
+
+/// A failure of the connection or of the HTTP/2 stream, instead of a gRPC response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Send the response headers then reset the stream (`RST_STREAM` with `INTERNAL_ERROR`).
+    ResetStream,
+    /// Close the connection without answering, like a crashed server. Needs a server created
+    /// [`with_faults`](crate::GrpcServer::with_faults).
+    CloseConnection,
+    /// Send a `GOAWAY` frame telling the client the request was not processed, then close the
+    /// connection. Over TLS or HTTP/1.1 the connection is closed without a `GOAWAY` frame.
+    /// Needs a server created [`with_faults`](crate::GrpcServer::with_faults).
+    GoAway,
+    /// Answer with the response headers only: no message, no trailers and no `grpc-status`.
+    EmptyResponse,
+}
+
+impl Fault {
+    /// The response of a rule failing with this fault. The connection faults never answer,
+    /// the request is dropped along with the connection.
+    pub(crate) async fn respond(
+        self,
+        connection: Option<Connection>,
+    ) -> http::Response<tonic::body::Body> {
+        let body = match (self, connection) {
+            (Fault::EmptyResponse, _) => tonic::body::Body::empty(),
+            (Fault::CloseConnection, Some(connection)) => {
+                connection.close();
+                std::future::pending().await
+            }
+            (Fault::GoAway, Some(connection)) => {
+                connection.go_away();
+                std::future::pending().await
+            }
+            (Fault::ResetStream, _) | (Fault::CloseConnection | Fault::GoAway, None) => {
+                if self != Fault::ResetStream {
+                    warn!("The connection is unknown, resetting the stream instead of {self:?}");
+                }
+                // hyper resets the stream when the body fails
+                let error = io::Error::other("stream reset by a fault of the mock server");
+                tonic::body::Body::new(StreamBody::new(tokio_stream::once(Err::<
+                    http_body::Frame<Bytes>,
+                    _,
+                >(error))))
+            }
+        };
+
+        let mut response = http::Response::new(body);
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            HeaderValue::from_static("application/grpc"),
+        );
+        response
+    }
+}
+
+/// What a rule asked the connection to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionFault {
+    Close,
+    GoAway,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    fault: Option<ConnectionFault>,
+    /// Wakes the task reading the connection, so that the fault is applied even when the
+    /// client sends nothing else.
+    waker: Option<Waker>,
+}
+
+/// Handle to a connection accepted by the server, available in the request extensions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Connection(Arc<Mutex<Shared>>);
+
+impl Connection {
+    pub(crate) fn close(&self) {
+        self.request(ConnectionFault::Close);
+    }
+
+    pub(crate) fn go_away(&self) {
+        self.request(ConnectionFault::GoAway);
+    }
+
+    fn request(&self, fault: ConnectionFault) {
+        let mut shared = self.0.lock().unwrap();
+        shared.fault.get_or_insert(fault);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn requested(&self, cx: &Context<'_>) -> Option<ConnectionFault> {
+        let mut shared = self.0.lock().unwrap();
+        match &shared.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => shared.waker = Some(cx.waker().clone()),
+        }
+        shared.fault
+    }
+}
+
+/// `GOAWAY` with the last stream id `0` (none of the requests were processed) and `NO_ERROR`.
+const GO_AWAY: [u8; 17] = [0, 0, 8, 0x7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+#[derive(Debug)]
+enum State {
+    Open,
+    /// Writing the `GOAWAY` frame, this many bytes are written.
+    GoingAway(usize),
+    Closed,
+}
+
+/// A connection the rules can break, see [`Fault`].
+#[derive(Debug)]
+pub(crate) struct FaultyIo<T> {
+    io: T,
+    connection: Connection,
+    state: State,
+    /// Whether the client started with the HTTP/2 preface, unknown until it sent something.
+    http2: Option<bool>,
+    frames: Frames,
+}
+
+impl<T> FaultyIo<T> {
+    pub(crate) fn new(io: T) -> Self {
+        Self {
+            io,
+            connection: Connection::default(),
+            state: State::Open,
+            http2: None,
+            frames: Frames::default(),
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin> FaultyIo<T> {
+    /// `Ready(Ok)` when the connection can be used, an error once closed by a fault.
+    fn poll_fault(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            match self.state {
+                State::Open => match self.connection.requested(cx) {
+                    None => return Poll::Ready(Ok(())),
+                    Some(ConnectionFault::GoAway) if self.http2 == Some(true) => {
+                        // never in the middle of a frame written by the server
+                        if !self.frames.at_boundary() {
+                            return Poll::Ready(Ok(()));
+                        }
+                        self.state = State::GoingAway(0);
+                    }
+                    Some(_) => self.state = State::Closed,
+                },
+                State::GoingAway(written) if written < GO_AWAY.len() => {
+                    let n = ready!(Pin::new(&mut self.io).poll_write(cx, &GO_AWAY[written..]))?;
+                    self.state = State::GoingAway(written + n);
+                }
+                State::GoingAway(_) => {
+                    ready!(Pin::new(&mut self.io).poll_flush(cx))?;
+                    self.state = State::Closed;
+                }
+                State::Closed => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "connection closed by a fault of the mock server",
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl<T: AsyncRead + AsyncWrite + Unpin> AsyncRead for FaultyIo<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        ready!(self.poll_fault(cx))?;
+
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut self.io).poll_read(cx, buf))?;
+        if self.http2.is_none() && buf.filled().len() > filled {
+            self.http2 = Some(buf.filled()[filled..].starts_with(b"PRI"));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for FaultyIo<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        ready!(self.poll_fault(cx))?;
+
+        let n = ready!(Pin::new(&mut self.io).poll_write(cx, buf))?;
+        self.frames.advance(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_fault(cx))?;
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+}
+
+impl<T> Connected for FaultyIo<T> {
+    type ConnectInfo = Connection;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        self.connection.clone()
+    }
+}
+
+/// Follows the HTTP/2 frames written by the server (9 bytes header, then the payload).
+#[derive(Debug, Default)]
+struct Frames {
+    header: [u8; 9],
+    header_len: usize,
+    payload_remaining: usize,
+}
+
+impl Frames {
+    fn at_boundary(&self) -> bool {
+        self.header_len == 0 && self.payload_remaining == 0
+    }
+
+    fn advance(&mut self, mut written: &[u8]) {
+        while !written.is_empty() {
+            if self.payload_remaining > 0 {
+                let n = self.payload_remaining.min(written.len());
+                self.payload_remaining -= n;
+                written = &written[n..];
+                continue;
+            }
+
+            let n = (self.header.len() - self.header_len).min(written.len());
+            self.header[self.header_len..self.header_len + n].copy_from_slice(&written[..n]);
+            self.header_len += n;
+            written = &written[n..];
+
+            if self.header_len == self.header.len() {
+                let [a, b, c, ..] = self.header;
+                self.payload_remaining = u32::from_be_bytes([0, a, b, c]) as usize;
+                self.header_len = 0;
+            }
+        }
+    }
+}
@@ -1,19 +1,36 @@
 use log::{debug, info, warn};
 use std::{
+    collections::VecDeque,
     net::{SocketAddr, TcpStream},
-    sync::{Arc, RwLock},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
     time::Duration,
 };
 
-use crate::tonic_ext::{GenericCodec, GenericSvc};
-use crate::MockBuilder;
+use crate::tonic_ext::{
+    accept_encodings, compression_encoding, grpc_timeout, missing_feature, GenericCodec,
+};
+use crate::wiremock::chaos::{Chaos, ChaosDecision};
+use crate::wiremock::fault::{Connection, Fault};
+use crate::wiremock::proxy::{Recorder, Recordings, Upstream};
+use crate::wiremock::shared::namespace;
+use crate::wiremock::transport::Transport;
+use crate::{MockBuilder, Mountable};
 use rand::Rng;
 use tonic::{
+    codec::{Codec, CompressionEncoding, EncodeBody, SingleMessageCompressionOverride, Streaming},
     codegen::{
         http::{self, HeaderMap, HeaderValue, Method},
+        tokio_stream::{self, StreamExt},
         Body, StdError,
     },
-    Code,
+    metadata::MetadataMap,
+    service::Routes,
+    transport::{Channel, Endpoint},
+    Code, Status,
 };
 
 /// A running gRPC server
@@ -36,12 +53,37 @@ use tonic::{
 #[derive(Clone, Debug)]
 pub struct GrpcServer {
     pub(crate) address: SocketAddr,
+    transport: Transport,
     inner: Arc<Option<Inner>>,
     pub(crate) rules: Arc<RwLock<Vec<RuleItem>>>,
+    upstream: Arc<RwLock<Option<Upstream>>>,
+    pub(crate) journal: Arc<RwLock<Journal>>,
+    /// See [`chaos`](Self::chaos).
+    pub(crate) chaos: Arc<Mutex<Option<Chaos>>>,
+    /// See [`with_faults`](Self::with_faults).
+    faults: bool,
+    /// Encode the JSON messages of the rules, see [`add_descriptors`](Self::add_descriptors).
+    #[cfg(feature = "json")]
+    pub(crate) descriptors: Arc<RwLock<prost_reflect::DescriptorPool>>,
+    /// Descriptor sets served by the reflection service, see [`with_reflection`](Self::with_reflection).
+    #[cfg(feature = "reflection")]
+    reflection: Vec<Arc<[u8]>>,
+    /// Statuses served by the health service, see [`with_health`](Self::with_health).
+    #[cfg(feature = "health")]
+    health: Option<tonic_health::server::HealthReporter>,
+    /// See [`with_grpc_web`](Self::with_grpc_web).
+    #[cfg(feature = "web")]
+    grpc_web: bool,
+    /// See [`start_admin`](Self::start_admin).
+    #[cfg(feature = "admin")]
+    pub(crate) admin: Arc<Mutex<Option<crate::wiremock::admin::AdminTask>>>,
 }
 
 #[derive(Debug)]
 pub(crate) struct RuleItem {
+    /// Identifies the rule in the admin API.
+    #[cfg_attr(not(feature = "admin"), allow(dead_code))]
+    pub(crate) id: u64,
     pub(crate) rule: MockBuilder,
 
     pub(crate) invocations_count: u32,
@@ -51,23 +93,123 @@ pub(crate) struct RuleItem {
 /// Represent a single handled request to the mock server.
 #[derive(Debug, Clone)]
 pub struct RequestItem {
+    /// Increases with every request received, by any server, see
+    /// [`GrpcServer::assert_called_in_order`].
+    pub sequence: u64,
     pub headers: HeaderMap,
     pub method: Method,
     pub uri: String,
+    /// The encoded request message (the first one for client streaming calls).
+    pub body: Vec<u8>,
+    /// The request was forwarded to the upstream server, see [`GrpcServer::proxy_unmatched_to`].
+    pub proxied: bool,
+    /// How the client compressed the request message (`grpc-encoding`), [`None`] when it did not.
+    pub encoding: Option<CompressionEncoding>,
+    /// The encodings the client accepts for the response (`grpc-accept-encoding`).
+    pub accept_encoding: Vec<CompressionEncoding>,
+    /// The time the client gives the server to respond (`grpc-timeout`), [`None`] when it set
+    /// no deadline.
+    pub deadline: Option<Duration>,
+    /// What the chaos mode did to the request, when enabled and a rule matched, see
+    /// [`GrpcServer::chaos`].
+    pub chaos: Option<ChaosDecision>,
+    /// The namespace of the [`SharedServer`](crate::SharedServer) the request was sent to, if
+    /// any.
+    pub namespace: Option<String>,
 }
 
-impl RuleItem {
-    fn record_request<B>(&mut self, r: &http::Request<B>)
-    where
-        B: Body + Send + 'static,
-        B::Error: Into<StdError> + Send + 'static,
-    {
-        self.invocations_count += 1;
-        self.invocations.push(RequestItem {
+impl RequestItem {
+    fn new(r: &http::Request<Vec<u8>>, proxied: bool) -> Self {
+        static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+        Self {
+            sequence: NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed),
             headers: r.headers().clone(),
             method: r.method().clone(),
             uri: r.uri().to_string(),
-        });
+            body: r.body().clone(),
+            proxied,
+            encoding: r
+                .headers()
+                .get("grpc-encoding")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|name| compression_encoding(name).ok().flatten()),
+            accept_encoding: accept_encodings(r.headers()),
+            deadline: grpc_timeout(r.headers()),
+            chaos: None,
+            namespace: namespace(r.uri(), r.headers()).map(str::to_string),
+        }
+    }
+
+    /// Decodes the request message as `T`.
+    pub fn decode_body<T: prost::Message + Default>(&self) -> Result<T, prost::DecodeError> {
+        T::decode(self.body.as_slice())
+    }
+}
+
+/// The requests received by a server, the oldest ones dropped past its capacity, see
+/// [`GrpcServer::journal_capacity`].
+#[derive(Debug)]
+pub(crate) struct Journal {
+    requests: VecDeque<RequestItem>,
+    capacity: usize,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            requests: VecDeque::new(),
+            capacity: DEFAULT_JOURNAL_CAPACITY,
+        }
+    }
+}
+
+impl Journal {
+    pub(crate) fn push(&mut self, request: RequestItem) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.requests.len() == self.capacity {
+            self.requests.pop_front();
+        }
+        self.requests.push_back(request);
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &RequestItem> {
+        self.requests.iter()
+    }
+
+    pub(crate) fn retain(&mut self, f: impl FnMut(&RequestItem) -> bool) {
+        self.requests.retain(f);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.requests.clear();
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let excess = self.requests.len().saturating_sub(capacity);
+        self.requests.drain(..excess);
+    }
+}
+
+/// How many requests the journal keeps by default, see [`GrpcServer::journal_capacity`].
+pub const DEFAULT_JOURNAL_CAPACITY: usize = 1000;
+
+impl RuleItem {
+    pub(crate) fn new(rule: MockBuilder) -> Self {
+        Self {
+            id: rule.id,
+            rule,
+            invocations_count: 0,
+            invocations: Vec::default(),
+        }
+    }
+
+    fn record_request(&mut self, request: RequestItem) {
+        self.invocations_count += 1;
+        self.invocations.push(request);
     }
 }
 
@@ -84,15 +226,14 @@ impl Drop for GrpcServer {
         if self.inner.as_ref().is_some() {
             info!("Terminating server");
 
+            // the copies made before starting (eg. by the handler) do not share `inner`
+            #[cfg(feature = "admin")]
+            if Arc::strong_count(&self.inner) == 1 {
+                self.admin.lock().unwrap().take();
+            }
+
             if self.rules_len() > 0 && self.rules_unmatched() > 0 {
-                let unmatched_paths = self
-                    .rules
-                    .read()
-                    .unwrap()
-                    .iter()
-                    .filter(|f| f.invocations_count == 0)
-                    .map(|f| f.rule.path.clone())
-                    .collect::<Vec<String>>();
+                let unmatched_paths = self.unmatched_paths();
 
                 self.reset();
                 panic!(
@@ -100,6 +241,14 @@ impl Drop for GrpcServer {
                     unmatched_paths.join("\n")
                 );
             }
+
+            #[cfg(unix)]
+            if let Transport::Uds(path) = &self.transport {
+                // the last handle to the server cleans up the socket file
+                if Arc::strong_count(&self.inner) == 1 {
+                    let _ = std::fs::remove_file(path);
+                }
+            }
         }
     }
 }
@@ -110,13 +259,70 @@ impl GrpcServer {
     }
 
     pub fn with_addr(address: SocketAddr) -> Self {
+        Self::with_transport(address, Transport::Tcp)
+    }
+
+    /// A server that is only reachable from this process, through [`channel`](Self::channel).
+    ///
+    /// No port is used, `address()` is meaningless for this server.
+    pub fn in_memory() -> Self {
+        Self::with_transport(Self::unbound_address(), Transport::in_memory())
+    }
+
+    /// A server listening to a Unix domain socket at `path`.
+    ///
+    /// `address()` is meaningless for this server.
+    #[cfg(unix)]
+    pub fn with_uds(path: impl Into<std::path::PathBuf>) -> Self {
+        Self::with_transport(Self::unbound_address(), Transport::Uds(path.into()))
+    }
+
+    /// A server using TLS, with the PEM encoded `certificate` and private `key`.
+    ///
+    /// The certificate must be valid for `localhost`, the [`channel`](Self::channel) trusts
+    /// this certificate as its root.
+    #[cfg(feature = "tls")]
+    pub fn with_tls(
+        address: SocketAddr,
+        certificate: impl AsRef<[u8]>,
+        key: impl AsRef<[u8]>,
+    ) -> Self {
+        Self::with_transport(
+            address,
+            Transport::Tls {
+                certificate: certificate.as_ref().to_vec(),
+                key: key.as_ref().to_vec(),
+            },
+        )
+    }
+
+    fn with_transport(address: SocketAddr, transport: Transport) -> Self {
         Self {
             address,
+            transport,
             inner: Arc::default(),
             rules: Arc::default(),
+            upstream: Arc::default(),
+            journal: Arc::default(),
+            chaos: Arc::default(),
+            faults: false,
+            #[cfg(feature = "json")]
+            descriptors: Arc::default(),
+            #[cfg(feature = "reflection")]
+            reflection: Vec::new(),
+            #[cfg(feature = "health")]
+            health: None,
+            #[cfg(feature = "web")]
+            grpc_web: false,
+            #[cfg(feature = "admin")]
+            admin: Arc::default(),
         }
     }
 
+    fn unbound_address() -> SocketAddr {
+        "[::1]:0".parse().unwrap()
+    }
+
     pub async fn find_unused_port() -> Option<u16> {
         let mut rng = rand::thread_rng();
 
@@ -140,6 +346,10 @@ impl GrpcServer {
         let thread = f;
 
         for _ in 0..40 {
+            if !self.transport.is_network() {
+                break;
+            }
+
             if TcpStream::connect_timeout(&self.address, std::time::Duration::from_millis(25))
                 .is_ok()
             {
@@ -155,18 +365,319 @@ impl GrpcServer {
         info!("Server started in {}", self.address());
     }
 
+    /// Start the server, answering the requests of any service.
+    ///
+    /// The servers generated by [`generate_svc!`](crate::generate_svc) only accept the requests
+    /// of their services, use this one when the services are only known at runtime.
+    /// ```no_run
+    /// # async fn example() {
+    /// let port = wiremock_grpc::GrpcServer::find_unused_port().await.unwrap();
+    /// let mut server = wiremock_grpc::GrpcServer::new(port).start().await;
+    /// # }
+    /// ```
+    pub async fn start(mut self) -> Self {
+        // not started yet, dropping this copy never verifies the rules
+        let handler = self.clone();
+        let mut routes = Routes::default();
+        let router = std::mem::take(routes.axum_router_mut());
+        *routes.axum_router_mut() =
+            router.fallback_service(tower::service_fn(move |req| handler.handle_request(req)));
+
+        self._serve(routes, &[]).await;
+        self
+    }
+
+    /// Serve the `grpc.reflection.v1` service, describing the services found in the encoded
+    /// `FileDescriptorSet` (eg. written by `protoc --include_imports --descriptor_set_out`),
+    /// so that tools like `grpcurl` can discover the mock.
+    ///
+    /// Only the mocked services are advertised (every service of the set for a server started
+    /// with [`start`](Self::start)). Call it before starting the server, several times to
+    /// serve several sets.
+    /// ```no_run
+    /// # wiremock_grpc::generate_svc! { package hello; service Greeter { SayHello } }
+    /// # async fn example() {
+    /// let server = wiremock_grpc::GrpcServer::new(50051)
+    ///     .with_reflection(std::fs::read("hello.bin").unwrap());
+    /// let server = GreeterMockServer::start_with(server).await;
+    /// # }
+    /// ```
+    #[cfg(feature = "reflection")]
+    pub fn with_reflection(mut self, file_descriptor_set: impl Into<Vec<u8>>) -> Self {
+        self.reflection.push(file_descriptor_set.into().into());
+        self
+    }
+
+    /// Serve the `grpc.health.v1` service (`Check` and `Watch`). The server (`""`) and the
+    /// mocked services are reported as `SERVING` until changed with
+    /// [`set_health`](Self::set_health).
+    ///
+    /// Call it before starting the server:
+    /// ```no_run
+    /// # wiremock_grpc::generate_svc! { package hello; service Greeter { SayHello } }
+    /// # async fn example() {
+    /// let server = GreeterMockServer::start_with(wiremock_grpc::GrpcServer::new(50051).with_health()).await;
+    /// # }
+    /// ```
+    #[cfg(feature = "health")]
+    pub fn with_health(mut self) -> Self {
+        self.health = Some(tonic_health::server::HealthReporter::new());
+        self
+    }
+
+    /// Change the status reported by the health service for `service` (eg. `hello.Greeter`,
+    /// or `""` for the whole server), notifying the `Watch` calls.
+    ///
+    /// ## Panics
+    /// * When the health service is not served, see [`with_health`](Self::with_health).
+    #[cfg(feature = "health")]
+    pub async fn set_health(&self, service: &str, status: tonic_health::ServingStatus) {
+        self.health
+            .as_ref()
+            .expect("The health service is not served, start the server `with_health()`")
+            .set_service_status(service, status)
+            .await;
+    }
+
+    /// Also accept gRPC-Web calls (`application/grpc-web` and `application/grpc-web-text`),
+    /// over HTTP/1.1 or HTTP/2, eg. from a browser. The rules and the journal are the same as
+    /// for gRPC calls, the trailers being sent in the response body.
+    ///
+    /// Cross-origin requests are allowed from anywhere, including the CORS preflight requests.
+    /// Call it before starting the server:
+    /// ```no_run
+    /// # wiremock_grpc::generate_svc! { package hello; service Greeter { SayHello } }
+    /// # async fn example() {
+    /// let server = GreeterMockServer::start_with(wiremock_grpc::GrpcServer::new(50051).with_grpc_web()).await;
+    /// # }
+    /// ```
+    #[cfg(feature = "web")]
+    pub fn with_grpc_web(mut self) -> Self {
+        self.grpc_web = true;
+        self
+    }
+
+    /// Let the rules close the connections, with [`Fault::CloseConnection`] and
+    /// [`Fault::GoAway`]. Every connection is then wrapped to be broken on demand, which costs a
+    /// little on each read and write: the servers without it serve the connections as they are.
+    ///
+    /// Call it before starting the server:
+    /// ```no_run
+    /// # wiremock_grpc::generate_svc! { package hello; service Greeter { SayHello } }
+    /// # async fn example() {
+    /// let server = GreeterMockServer::start_with(wiremock_grpc::GrpcServer::new(50051).with_faults()).await;
+    /// # }
+    /// ```
+    pub fn with_faults(mut self) -> Self {
+        self.faults = true;
+        self
+    }
+
+    /// ## Panics
+    /// * When `rule` breaks the connection and the server was not created
+    ///   [`with_faults`](Self::with_faults).
+    fn check_fault(&self, rule: &MockBuilder) {
+        if let Some(fault @ (Fault::CloseConnection | Fault::GoAway)) = rule.fault {
+            if !self.faults {
+                panic!(
+                    "Fault::{fault:?} needs a server created with `with_faults()`, \
+                     eg. `GrpcServer::new(port).with_faults()`"
+                );
+            }
+        }
+    }
+
+    /// Serve `routes` using the transport the server was created with.
+    ///
+    /// `services` are the names of the mocked services, empty when any service is.
+    #[doc(hidden)]
+    pub async fn _serve(&mut self, routes: Routes, services: &[&str]) {
+        #[cfg(feature = "reflection")]
+        let routes = if self.reflection.is_empty() {
+            routes
+        } else {
+            routes.add_service(self.reflection_service(services))
+        };
+        #[cfg(feature = "health")]
+        let routes = match &self.health {
+            Some(reporter) => {
+                for service in services {
+                    reporter
+                        .set_service_status(service, tonic_health::ServingStatus::Serving)
+                        .await;
+                }
+                routes.add_service(tonic_health::pb::health_server::HealthServer::new(
+                    tonic_health::server::HealthService::from_health_reporter(reporter.clone()),
+                ))
+            }
+            None => routes,
+        };
+        #[cfg(not(any(feature = "reflection", feature = "health")))]
+        let _ = services;
+
+        #[cfg(feature = "web")]
+        let (routes, http1) = {
+            let mut routes = routes;
+            if self.grpc_web {
+                // CORS first: the preflight requests are not gRPC-Web calls
+                let router = std::mem::take(routes.axum_router_mut());
+                *routes.axum_router_mut() = router
+                    .layer(tonic_web::GrpcWebLayer::new())
+                    .layer(tower_http::cors::CorsLayer::permissive());
+            }
+            (routes, self.grpc_web)
+        };
+        #[cfg(not(feature = "web"))]
+        let http1 = false;
+
+        let thread = self
+            .transport
+            .serve(self.address, routes, http1, self.faults);
+        self._start(thread).await;
+    }
+
+    /// A [`Channel`] connected to this server, using the transport the server was started on.
+    ///
+    /// ## Panics
+    /// * When unable to connect to the server.
+    pub async fn channel(&self) -> Channel {
+        self.transport
+            .channel(self.address)
+            .await
+            .expect("Unable to connect to the mock server")
+    }
+
+    /// A gRPC client connected to this server, built by `new` from the
+    /// [`channel`](Self::channel), eg. the constructor of any tonic client.
+    /// ```no_run
+    /// # async fn example(server: wiremock_grpc::GrpcServer) {
+    /// let mut client = server.client(GreeterClient::new).await;
+    /// # }
+    /// ```
+    pub async fn client<C>(&self, new: impl FnOnce(Channel) -> C) -> C {
+        new(self.channel().await)
+    }
+
+    /// Set up the rule `r`, returning it to inspect its requests with [`find`](Self::find).
     pub fn setup<M>(&mut self, r: M) -> MockBuilder
     where
-        M: Into<MockBuilder> + Clone + crate::Mountable,
+        M: Into<MockBuilder> + Clone + Mountable,
     {
-        r.clone().mount(self);
+        self.add_rule(r.into())
+    }
+
+    /// Adds `rule`, its JSON messages encoded, and returns it as matched by the server.
+    ///
+    /// ## Panics
+    /// * When the rule is invalid, see [`check_fault`](Self::check_fault) and
+    ///   [`into_rule_item`](MockBuilder::into_rule_item).
+    pub(crate) fn add_rule(&self, rule: MockBuilder) -> MockBuilder {
+        #[cfg(feature = "json")]
+        let rule = self.encode_json(rule);
+
+        self.check_fault(&rule);
+        let item = rule.into_rule_item();
+        let rule = item.rule.clone();
+        self.rules.write().unwrap().push(item);
+
+        rule
+    }
 
-        r.into()
+    /// Record mode: forward the requests no rule matches to `upstream` and write each request
+    /// and the response (status, headers, messages and trailers) to the JSON file at `path`.
+    ///
+    /// Load the file in a later run with [`replay_from`](Self::replay_from):
+    /// ```no_run
+    /// # async fn example(mut server: wiremock_grpc::GrpcServer) -> std::io::Result<()> {
+    /// let recording = "tests/recordings/say_hello.json";
+    /// if std::env::var("RECORD").is_ok() {
+    ///     let upstream = tonic::transport::Channel::from_static("http://localhost:50051")
+    ///         .connect()
+    ///         .await
+    ///         .unwrap();
+    ///     server.record_to(upstream, recording);
+    /// } else {
+    ///     server.replay_from(recording)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn record_to(&self, upstream: Channel, path: impl AsRef<Path>) {
+        *self.upstream.write().unwrap() = Some(Upstream {
+            channel: upstream,
+            recorder: Some(Recorder::new(path.as_ref().to_path_buf())),
+        });
+    }
+
+    /// Forward the requests no rule matches to `endpoint`, verbatim (including streaming calls),
+    /// and relay its response. This allows mocking only some rpc of a real server.
+    ///
+    /// Forwarded requests are part of the [`journal`](Self::journal), marked as
+    /// [`proxied`](RequestItem::proxied).
+    /// ```no_run
+    /// # fn example(server: wiremock_grpc::GrpcServer) {
+    /// server.proxy_unmatched_to(tonic::transport::Endpoint::from_static("http://localhost:50051"));
+    /// # }
+    /// ```
+    pub fn proxy_unmatched_to(&self, endpoint: Endpoint) {
+        *self.upstream.write().unwrap() = Some(Upstream {
+            channel: endpoint.connect_lazy(),
+            recorder: None,
+        });
     }
 
-    /// Reset all mappings
+    /// Set up a rule for each request recorded with [`record_to`](Self::record_to), replying
+    /// with the recorded response when the same rpc is called with the same message.
+    ///
+    /// Only the first message of a streaming response is replayed. Like any other rule,
+    /// each recorded request must be made before the server is dropped.
+    pub fn replay_from(&mut self, path: impl AsRef<Path>) -> std::io::Result<Vec<MockBuilder>> {
+        let rules = Recordings::load(path.as_ref())?.into_rules()?;
+        Ok(rules.into_iter().map(|rule| self.setup(rule)).collect())
+    }
+
+    /// ## Panics
+    /// * When a descriptor set is invalid.
+    #[cfg(feature = "reflection")]
+    fn reflection_service(
+        &self,
+        services: &[&str],
+    ) -> tonic_reflection::server::v1::ServerReflectionServer<
+        impl tonic_reflection::server::v1::ServerReflection,
+    > {
+        use prost::Message;
+
+        let mut builder = tonic_reflection::server::Builder::configure();
+        for set in &self.reflection {
+            let set = prost_types::FileDescriptorSet::decode(&set[..])
+                .expect("Invalid descriptor set for the reflection service");
+            builder = builder.register_file_descriptor_set(set);
+        }
+        if !services.is_empty() {
+            builder = builder.with_service_name(
+                tonic_reflection::pb::v1::server_reflection_server::SERVICE_NAME,
+            );
+            for service in services {
+                builder = builder.with_service_name(*service);
+            }
+        }
+
+        builder
+            .build_v1()
+            .expect("Invalid descriptor set for the reflection service")
+    }
+
+    /// Keep the last `capacity` requests in the [`journal`](Self::journal) (the oldest ones
+    /// are dropped), [`DEFAULT_JOURNAL_CAPACITY`] unless changed. `0` disables the journal,
+    /// the requests matched by each rule are still kept for [`find`](Self::find).
+    pub fn journal_capacity(&self, capacity: usize) {
+        self.journal.write().unwrap().set_capacity(capacity);
+    }
+
+    /// Reset all mappings and the journal
     pub fn reset(&self) {
         self.rules.write().unwrap().clear();
+        self.journal.write().unwrap().clear();
     }
 
     pub fn address(&self) -> &SocketAddr {
@@ -183,63 +694,198 @@ impl GrpcServer {
     {
         info!("Request to {}", req.uri().path());
 
-        let mut inner = self.rules.write().unwrap();
-
-        if let Some(item) = inner.iter_mut().find(|x| x.rule.matches(&req)) {
-            info!("Matched rule {:?}", item);
-            item.record_request(&req);
-
-            let code = item.rule.status_code.unwrap_or(Code::Ok);
-            let return_headers = item.rule.response_headers.clone();
-            if let Some(body) = &item.rule.result {
-                debug!("Returning body ({} bytes)", body.len());
-                let body = body.clone();
-
-                let fut = async move {
-                    let method = GenericSvc(body);
-                    let codec = GenericCodec {};
-
-                    let mut grpc = tonic::server::Grpc::new(codec);
-                    let mut result = grpc.unary(method, req).await;
-
-                    let headers = result.headers_mut();
-                    headers.append(
-                        "grpc-status",
-                        HeaderValue::from_str(format!("{}", code as u32).as_str()).unwrap(),
+        let rules = self.rules.clone();
+        let upstream = self.upstream.clone();
+        let journal = self.journal.clone();
+        let chaos = self.chaos.clone();
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+
+            let encoding = match parts.headers.get("grpc-encoding") {
+                None => None,
+                Some(value) => match compression_encoding(value.to_str().unwrap_or_default()) {
+                    Ok(encoding) => encoding,
+                    Err(name) => {
+                        warn!("Unsupported request encoding `{name}`");
+                        let message = match missing_feature(name) {
+                            Some(feature) => format!(
+                                "Content is compressed with `{name}`, enable the `{feature}` \
+                                 feature of wiremock-grpc to accept it"
+                            ),
+                            None => {
+                                format!("Content is compressed with `{name}` which isn't supported")
+                            }
+                        };
+                        return Ok(Status::unimplemented(message).into_http());
+                    }
+                },
+            };
+
+            let mut codec = GenericCodec;
+            let mut messages = Streaming::new_request(codec.decoder(), body, encoding, None);
+            let first = match messages.message().await {
+                Ok(message) => message,
+                Err(status) => {
+                    warn!("Unable to decode the request: {status}");
+                    return Ok(status.into_http());
+                }
+            };
+
+            let connection = connection(&parts.extensions);
+            let req = http::Request::from_parts(parts, first.clone().unwrap_or_default());
+
+            let matched = {
+                let mut inner = rules.write().unwrap();
+                inner.iter_mut().find(|x| x.rule.matches(&req)).map(|item| {
+                    info!("Matched rule {:?}", item);
+                    let mut request = RequestItem::new(&req, false);
+                    // decided while holding the rules, in the order of the journal
+                    request.chaos = chaos.lock().unwrap().as_mut().map(Chaos::decide);
+                    journal.write().unwrap().push(request.clone());
+                    item.record_request(request.clone());
+                    (item.rule.clone(), request.chaos)
+                })
+            };
+
+            let Some((rule, decision)) = matched else {
+                let upstream = upstream.read().unwrap().clone();
+                if let Some(upstream) = upstream {
+                    journal.write().unwrap().push(RequestItem::new(&req, true));
+                    let (parts, _) = req.into_parts();
+                    let messages = tokio_stream::iter(first.map(Ok)).chain(messages);
+                    return Ok(upstream.forward(parts, messages).await);
+                }
+
+                warn!("Request unhandled");
+                journal.write().unwrap().push(RequestItem::new(&req, false));
+                return Ok(Status::unimplemented("").into_http());
+            };
+
+            let latency = decision.map_or(Duration::ZERO, |d| d.latency);
+            let delay = rule.delay.unwrap_or_default() + latency;
+            if !delay.is_zero() {
+                // like a real server, give up once the deadline is exceeded. The headers are
+                // sent right away: tonic answers `Cancelled` when the handler itself times out.
+                if let Some(deadline) = grpc_timeout(req.headers()).filter(|d| delay > *d) {
+                    debug!("The delay of {delay:?} exceeds the deadline of {deadline:?}");
+                    let status =
+                        tokio_stream::iter([Err(Status::deadline_exceeded("Timeout expired"))])
+                            .then(move |status| async move {
+                                tokio::time::sleep(deadline).await;
+                                status
+                            });
+                    let body = EncodeBody::new_server(
+                        codec.encoder(),
+                        status,
+                        None,
+                        SingleMessageCompressionOverride::default(),
+                        None,
                     );
+                    return Ok(grpc_response(
+                        tonic::body::Body::new(body),
+                        None,
+                        HeaderMap::new(),
+                    ));
+                }
+
+                debug!("Delaying the response by {delay:?}");
+                tokio::time::sleep(delay).await;
+            }
 
-                    for (name, value) in return_headers {
-                        if let Some(name) = name {
-                            headers.insert(name, value);
-                        }
-                    }
+            if let Some(decision) = decision {
+                info!("Chaos: {decision:?}");
+                if let Some(code) = decision.code {
+                    return Ok(Status::new(code, "failed by the chaos mode").into_http());
+                }
+            }
 
-                    Ok(result)
-                };
-                return Box::pin(fut);
+            if let Some(fault) = rule.fault {
+                info!("Failing with {fault:?}");
+                return Ok(fault.respond(connection).await);
             }
-            let status = code as u32;
-            let builder = http::Response::builder()
-                .status(200)
-                .header("content-type", "application/grpc")
-                .header("grpc-status", format!("{status}"));
-            info!("Returning empty body with status {}", status);
-
-            return Box::pin(async move {
-                let body = builder.body(tonic::body::Body::default()).unwrap();
-                Ok(body)
-            });
-        }
 
-        warn!("Request unhandled");
-        let builder = http::Response::builder()
-            .status(200)
-            .header("content-type", "application/grpc")
-            .header("grpc-status", format!("{}", Code::Unimplemented as u32));
+            let code = rule.status_code.unwrap_or(Code::Ok);
+            let status = Status::with_metadata(
+                code,
+                rule.status_message.unwrap_or_default(),
+                MetadataMap::from_headers(rule.response_trailers),
+            );
+            if let Some(frame) = rule.raw_frame {
+                debug!("Returning raw frame ({} bytes)", frame.len());
+                let mut trailers = HeaderMap::new();
+                if let Err(e) = status.add_header(&mut trailers) {
+                    warn!("Unable to send the status {e}");
+                }
+                let frames = [
+                    http_body::Frame::data(prost::bytes::Bytes::from(frame)),
+                    http_body::Frame::trailers(trailers),
+                ];
+                let body = http_body_util::StreamBody::new(tokio_stream::iter(
+                    frames.map(Ok::<_, std::convert::Infallible>),
+                ));
+                return Ok(grpc_response(
+                    tonic::body::Body::new(body),
+                    rule.compression,
+                    rule.response_headers,
+                ));
+            }
 
-        Box::pin(async move {
-            let body = builder.body(tonic::body::Body::empty()).unwrap();
-            Ok(body)
+            let Some(body) = rule.result.map(|body| body.encode()) else {
+                info!("Returning empty body with status {}", code as u32);
+                let mut response = status.into_http();
+                response.headers_mut().extend(rule.response_headers);
+                return Ok(response);
+            };
+
+            debug!("Returning body ({} bytes)", body.len());
+            let mut messages = vec![Ok(body)];
+            if code != Code::Ok || !status.metadata().is_empty() || !status.message().is_empty() {
+                messages.push(Err(status));
+            }
+            let body = EncodeBody::new_server(
+                codec.encoder(),
+                tokio_stream::iter(messages),
+                rule.compression,
+                SingleMessageCompressionOverride::default(),
+                None,
+            );
+
+            Ok(grpc_response(
+                tonic::body::Body::new(body),
+                rule.compression,
+                rule.response_headers,
+            ))
         })
     }
 }
+
+/// A response carrying gRPC messages in `body`, compressed with `compression`.
+fn grpc_response(
+    body: tonic::body::Body,
+    compression: Option<CompressionEncoding>,
+    extra_headers: HeaderMap,
+) -> http::Response<tonic::body::Body> {
+    let mut response = http::Response::new(body);
+    let headers = response.headers_mut();
+    headers.insert(
+        http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/grpc"),
+    );
+    if let Some(encoding) = compression {
+        headers.insert(
+            "grpc-encoding",
+            HeaderValue::from_str(&encoding.to_string()).unwrap(),
+        );
+    }
+    headers.extend(extra_headers);
+    response
+}
+
+/// The connection the request was received on, see [`Fault`](crate::Fault).
+fn connection(extensions: &http::Extensions) -> Option<Connection> {
+    #[cfg(feature = "tls")]
+    if let Some(info) = extensions.get::<tonic::transport::server::TlsConnectInfo<Connection>>() {
+        return Some(info.get_ref().clone());
+    }
+    extensions.get::<Connection>().cloned()
+}
@@ -1,7 +1,8 @@
 use crate::{wiremock::grpc_server::RequestItem, GrpcServer, MockBuilder};
 
 impl GrpcServer {
-    /// Finds one or more matched requests for a given request builder.
+    /// Finds one or more matched requests for a given request builder, the one returned by
+    /// `setup()` (or a clone of the builder given to it).
     ///
     /// ## Returns
     /// * [`None`]: when the given [`MockBuilder`] is not registered using the `setup()` function.
@@ -39,6 +40,67 @@ impl GrpcServer {
         }
     }
 
+    /// Checks that the rules were called in the given order, eg. `Login` before `Fetch` before
+    /// `Logout`. Other calls may happen in between, and a rule may be called again later.
+    /// ```no_run
+    /// # use wiremock_grpc::*;
+    /// # fn example(server: GrpcServer, login: MockBuilder, fetch: MockBuilder, logout: MockBuilder) {
+    /// server.assert_called_in_order(&[&login, &fetch, &logout]);
+    /// # }
+    /// ```
+    ///
+    /// ## Panics
+    /// * When the rules were not called in this order, with the calls received by the server.
+    /// * When a rule is not registered with the server using the `setup()` function.
+    pub fn assert_called_in_order(&self, rules: &[&MockBuilder]) {
+        let calls: Vec<Vec<u64>> = rules
+            .iter()
+            .map(|rule| {
+                self.find(rule)
+                    .expect("The given MockBuilder is not registered with the mock server.")
+                    .iter()
+                    .map(|request| request.sequence)
+                    .collect()
+            })
+            .collect();
+
+        // the earliest call of each rule following the call of the previous one
+        let mut previous = 0;
+        let in_order = calls.iter().all(|sequences| {
+            match sequences.iter().find(|sequence| **sequence > previous) {
+                Some(sequence) => {
+                    previous = *sequence;
+                    true
+                }
+                None => false,
+            }
+        });
+
+        if !in_order {
+            let expected = rules
+                .iter()
+                .enumerate()
+                .map(|(i, rule)| format!("  {}. {}", i + 1, rule.path))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let timeline = self
+                .journal()
+                .iter()
+                .map(|request| {
+                    let rules = calls
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, sequences)| sequences.contains(&request.sequence))
+                        .map(|(i, _)| format!(" (rule {})", i + 1))
+                        .collect::<String>();
+                    format!("  #{} {}{rules}", request.sequence, request.uri)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            panic!("The rules were not called in order:\n{expected}\nCalls received:\n{timeline}");
+        }
+    }
+
     /// Returns number of handled requests
     pub fn find_request_count(&self) -> u32 {
         let mut count = 0;
@@ -48,6 +110,12 @@ impl GrpcServer {
         count
     }
 
+    /// Returns every request received by the server, in order, whether a rule matched it or not.
+    /// Only the last ones are kept, see [`GrpcServer::journal_capacity`].
+    pub fn journal(&self) -> Vec<RequestItem> {
+        self.journal.read().unwrap().iter().cloned().collect()
+    }
+
     /// Return number of rules registered with the server
     pub fn rules_len(&self) -> usize {
         self.rules.read().unwrap().iter().len()
@@ -62,12 +130,37 @@ impl GrpcServer {
             .filter(|f| f.invocations_count == 0)
             .count()
     }
+
+    /// Checks that every rule was matched, as done when the server is dropped, eg. to verify a
+    /// [`BlockingServer`](crate::BlockingServer) at a given point of a `#[test]`.
+    ///
+    /// ## Panics
+    /// * When a rule was never matched. The rules are removed first, so that dropping the
+    ///   server while unwinding does not panic again.
+    pub fn assert_all_matched(&self) {
+        let unmatched = self.unmatched_paths();
+        if !unmatched.is_empty() {
+            self.reset();
+            panic!("Rules never matched: \n{}", unmatched.join("\n"));
+        }
+    }
+
+    pub(crate) fn unmatched_paths(&self) -> Vec<String> {
+        self.rules
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|f| f.invocations_count == 0)
+            .map(|f| f.rule.path.clone())
+            .collect()
+    }
 }
 
+/// Rules are equal when they are the same rule: a builder and its clones, eg. the one given to
+/// or returned by [`GrpcServer::setup`], but not two rules built alike. Each [`Then`](crate::Then)
+/// method returns a new rule, so two rules completed from one base differ.
 impl PartialEq for MockBuilder {
     fn eq(&self, other: &Self) -> bool {
-        self.path == other.path
-            && self.status_code == other.status_code
-            && self.result == other.result
+        self.id == other.id
     }
 }
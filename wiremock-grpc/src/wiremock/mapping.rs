@@ -0,0 +1,285 @@
+//! Stub mappings: rules written as JSON (or YAML) documents instead of Rust code.
+//!
+//! The messages are written as JSON too and converted to the wire format using the
+//! descriptors of the services (see [`prost_reflect::DescriptorPool`]).
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tonic::{
+    codegen::http::{HeaderMap, HeaderName, HeaderValue},
+    Code,
+};
+
+use crate::wiremock::builder::{BodyMatcher, ResponseBody};
+use crate::{GrpcServer, MockBuilder};
+
+/// A rule, as read from a stub mapping file.
+///
+/// ```json
+/// {
+///   "request": {
+///     "path": "/hello.Greeter/SayHello",
+///     "headers": { "x-session-id": "abc123" },
+///     "body": { "contains": { "name": "Alice" } }
+///   },
+///   "response": {
+///     "status": "OK",
+///     "body": { "message": "Hello Alice" },
+///     "headers": { "x-server": "mock" },
+///     "delayMs": 100
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mapping {
+    pub request: RequestPattern,
+    #[serde(default)]
+    pub response: ResponseDefinition,
+}
+
+/// What a request must look like to match a [`Mapping`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestPattern {
+    /// The rpc path, eg. `/hello.Greeter/SayHello`.
+    pub path: String,
+    /// Headers (metadata) the request must have, with these exact values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<BodyPattern>,
+}
+
+/// How the request message is compared, see [`WhenBuilder::body_eq`](crate::WhenBuilder::body_eq)
+/// and [`WhenBuilder::body_contains`](crate::WhenBuilder::body_contains).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum BodyPattern {
+    EqualTo(serde_json::Value),
+    Contains(serde_json::Value),
+}
+
+/// The response sent when a request matches a [`Mapping`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ResponseDefinition {
+    #[serde(default)]
+    pub status: StatusCode,
+    /// The response message, as JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub trailers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+/// A gRPC status code, written as a number (`5`) or a name (`"NOT_FOUND"` or `"NotFound"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusCode(pub Code);
+
+impl Default for StatusCode {
+    fn default() -> Self {
+        Self(Code::Ok)
+    }
+}
+
+impl Serialize for StatusCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&status_name(self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(i32),
+            Name(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(n) if (0..=16).contains(&n) => Ok(Self(Code::from(n))),
+            Raw::Number(n) => Err(serde::de::Error::custom(format!("invalid status code {n}"))),
+            Raw::Name(name) => {
+                let normalized = name.replace('_', "").to_ascii_lowercase();
+                (0..=16)
+                    .map(Code::from)
+                    .find(|code| {
+                        status_name(*code).replace('_', "").to_ascii_lowercase() == normalized
+                    })
+                    .map(Self)
+                    .ok_or_else(|| {
+                        serde::de::Error::custom(format!("invalid status code `{name}`"))
+                    })
+            }
+        }
+    }
+}
+
+/// `NOT_FOUND` for [`Code::NotFound`].
+fn status_name(code: Code) -> String {
+    let mut name = String::new();
+    for (i, ch) in format!("{code:?}").chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.push(ch.to_ascii_uppercase());
+    }
+    name
+}
+
+/// A [`Mapping`] that cannot be turned into a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingError(String);
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for MappingError {}
+
+impl Mapping {
+    /// Builds the rule, encoding the JSON messages with the descriptors found in `pool`.
+    pub fn to_rule(&self, pool: &DescriptorPool) -> Result<MockBuilder, MappingError> {
+        find_method(pool, &self.request.path)?;
+
+        let mut rule = MockBuilder::given(&self.request.path);
+        rule.request_headers = header_map(&self.request.headers)?;
+        rule.request_body = self.request.body.as_ref().map(|body| match body {
+            BodyPattern::EqualTo(json) => BodyMatcher::Json {
+                json: json.clone(),
+                partial: false,
+            },
+            BodyPattern::Contains(json) => BodyMatcher::Json {
+                json: json.clone(),
+                partial: true,
+            },
+        });
+
+        let response = &self.response;
+        rule.status_code = Some(response.status.0);
+        rule.json_result = response.body.clone();
+        rule.response_headers = header_map(&response.headers)?;
+        rule.response_trailers = header_map(&response.trailers)?;
+        rule.delay = response.delay_ms.map(Duration::from_millis);
+
+        rule.encode_json(pool)
+    }
+}
+
+impl MockBuilder {
+    /// Encodes the messages written as JSON, see [`Then::return_json`](crate::Then::return_json)
+    /// and [`WhenBuilder::body_json`](crate::WhenBuilder::body_json).
+    pub(crate) fn encode_json(mut self, pool: &DescriptorPool) -> Result<Self, MappingError> {
+        let json_body = matches!(self.request_body, Some(BodyMatcher::Json { .. }));
+        if !json_body && self.json_result.is_none() {
+            return Ok(self);
+        }
+
+        let method = find_method(pool, &self.path)?;
+        if let Some(BodyMatcher::Json { json, partial }) = &self.request_body {
+            let message = encode(&method.input(), json)?;
+            self.request_body = Some(if *partial {
+                BodyMatcher::Partial(message)
+            } else {
+                BodyMatcher::Exact(message)
+            });
+        }
+        if let Some(json) = self.json_result.take() {
+            self.result = Some(ResponseBody::fixed(encode(&method.output(), &json)?));
+        }
+
+        Ok(self)
+    }
+}
+
+impl GrpcServer {
+    /// Load the descriptors of a `FileDescriptorSet` (eg. written by
+    /// `protoc --include_imports --descriptor_set_out`), used to encode the messages written
+    /// as JSON with [`Then::return_json`](crate::Then::return_json) and
+    /// [`WhenBuilder::body_json`](crate::WhenBuilder::body_json).
+    ///
+    /// Can be called several times to load several sets.
+    /// ```no_run
+    /// # use wiremock_grpc::*;
+    /// # fn example(mut server: GrpcServer) {
+    /// server
+    ///     .add_descriptors(std::fs::read("hello.bin").unwrap())
+    ///     .unwrap();
+    /// server.setup(
+    ///     MockBuilder::given("/hello.Greeter/SayHello").return_json(r#"{"message": "hi"}"#),
+    /// );
+    /// # }
+    /// ```
+    pub fn add_descriptors(
+        &self,
+        file_descriptor_set: impl AsRef<[u8]>,
+    ) -> Result<(), prost_reflect::DescriptorError> {
+        self.descriptors
+            .write()
+            .unwrap()
+            .decode_file_descriptor_set(file_descriptor_set.as_ref())
+    }
+
+    /// ## Panics
+    /// * When a message cannot be encoded.
+    pub(crate) fn encode_json(&self, rule: MockBuilder) -> MockBuilder {
+        let path = rule.path.clone();
+        rule.encode_json(&self.descriptors.read().unwrap())
+            .unwrap_or_else(|e| {
+                panic!("Unable to encode the JSON messages of {path} (see `add_descriptors`): {e}")
+            })
+    }
+}
+
+/// Finds the rpc of a path like `/hello.Greeter/SayHello`.
+pub(crate) fn find_method(
+    pool: &DescriptorPool,
+    path: &str,
+) -> Result<MethodDescriptor, MappingError> {
+    let (service, method) = path
+        .strip_prefix('/')
+        .and_then(|p| p.split_once('/'))
+        .ok_or_else(|| {
+            MappingError(format!(
+                "invalid path `{path}`, expected `/package.Service/Method`"
+            ))
+        })?;
+
+    let service = pool
+        .get_service_by_name(service)
+        .ok_or_else(|| MappingError(format!("unknown service `{service}` in `{path}`")))?;
+    let found = service.methods().find(|m| m.name() == method);
+    found.ok_or_else(|| MappingError(format!("unknown method `{method}` in `{path}`")))
+}
+
+fn encode(
+    descriptor: &MessageDescriptor,
+    json: &serde_json::Value,
+) -> Result<Vec<u8>, MappingError> {
+    let message = DynamicMessage::deserialize(descriptor.clone(), json)
+        .map_err(|e| MappingError(format!("invalid `{}` message: {e}", descriptor.full_name())))?;
+    Ok(message.encode_to_vec())
+}
+
+fn header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap, MappingError> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::try_from(name)
+            .map_err(|e| MappingError(format!("invalid header name `{name}`: {e}")))?;
+        let value = HeaderValue::try_from(value)
+            .map_err(|e| MappingError(format!("invalid value for header `{name}`: {e}")))?;
+        map.append(name, value);
+    }
+    Ok(map)
+}
@@ -0,0 +1,88 @@
+//! Custom request matchers, see [`WhenBuilder::matching`](crate::WhenBuilder::matching).
+
+use std::{fmt, sync::Arc};
+
+use tonic::{
+    codegen::http::{self, HeaderMap},
+    metadata::MetadataMap,
+};
+
+/// A request, as seen by a [`Match`].
+#[derive(Debug, Clone, Copy)]
+pub struct GrpcRequest<'a> {
+    request: &'a http::Request<Vec<u8>>,
+}
+
+impl<'a> GrpcRequest<'a> {
+    /// `request` carries the first request message as its body.
+    pub(crate) fn new(request: &'a http::Request<Vec<u8>>) -> Self {
+        Self { request }
+    }
+
+    /// The rpc path, eg. `/hello.Greeter/SayHello`.
+    pub fn path(&self) -> &'a str {
+        self.request.uri().path()
+    }
+
+    /// The uri of the request, with its host.
+    pub(crate) fn uri(&self) -> &'a http::Uri {
+        self.request.uri()
+    }
+
+    /// The headers of the request, including the metadata.
+    pub fn headers(&self) -> &'a HeaderMap {
+        self.request.headers()
+    }
+
+    /// The metadata of the request, the way a tonic service sees it.
+    pub fn metadata(&self) -> MetadataMap {
+        MetadataMap::from_headers(self.request.headers().clone())
+    }
+
+    /// The encoded request message (the first one for client streaming calls).
+    pub fn body(&self) -> &'a [u8] {
+        self.request.body()
+    }
+
+    /// Decodes the request message as `T`.
+    pub fn decode_body<T: prost::Message + Default>(&self) -> Result<T, prost::DecodeError> {
+        T::decode(self.body())
+    }
+}
+
+/// A condition on the requests a rule matches, for the checks no built-in matcher covers.
+///
+/// Implemented by the closures taking a [`GrpcRequest`]:
+/// ```no_run
+/// # use wiremock_grpc::*;
+/// MockBuilder::when()
+///     .path("/hello.Greeter/SayHello")
+///     .matching(|req: &GrpcRequest| {
+///         req.headers()
+///             .get("authorization")
+///             .is_some_and(|value| value.as_bytes().starts_with(b"Bearer "))
+///     })
+/// # ;
+/// ```
+pub trait Match: Send + Sync + 'static {
+    fn matches(&self, request: &GrpcRequest<'_>) -> bool;
+}
+
+impl<F> Match for F
+where
+    F: Fn(&GrpcRequest<'_>) -> bool + Send + Sync + 'static,
+{
+    fn matches(&self, request: &GrpcRequest<'_>) -> bool {
+        self(request)
+    }
+}
+
+/// A [`Match`] shared by the copies of a rule.
+#[derive(Clone)]
+pub(crate) struct Matcher(pub(crate) Arc<dyn Match>);
+
+impl fmt::Debug for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Matcher(..)")
+    }
+}
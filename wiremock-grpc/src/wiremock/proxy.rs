@@ -0,0 +1,289 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http_body_util::{BodyExt, StreamBody};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tonic::{
+    body::Body,
+    codec::{Codec, EncodeBody},
+    codegen::{
+        http::{self, request, HeaderMap, HeaderName, HeaderValue},
+        tokio_stream::{self, Stream},
+    },
+    transport::Channel,
+    Code, Status,
+};
+use tower::ServiceExt;
+
+use crate::tonic_ext::GenericCodec;
+use crate::wiremock::builder::{BodyMatcher, ResponseBody};
+use crate::MockBuilder;
+
+/// The server unmatched requests are forwarded to.
+#[derive(Debug, Clone)]
+pub(crate) struct Upstream {
+    pub(crate) channel: Channel,
+    pub(crate) recorder: Option<Recorder>,
+}
+
+impl Upstream {
+    /// Sends the request to the upstream server and returns its response.
+    pub(crate) async fn forward<S>(
+        &self,
+        mut parts: request::Parts,
+        messages: S,
+    ) -> http::Response<Body>
+    where
+        S: Stream<Item = Result<Vec<u8>, Status>> + Send + 'static,
+    {
+        let Some(recorder) = &self.recorder else {
+            return match self.send(parts, messages).await {
+                Ok(response) => response,
+                Err(status) => status.into_http(),
+            };
+        };
+
+        // the recorded messages must be readable
+        parts.headers.remove("grpc-accept-encoding");
+
+        let (messages, request) = {
+            use tokio_stream::StreamExt;
+
+            let messages: Vec<_> = messages.collect().await;
+            let request = messages
+                .iter()
+                .flatten()
+                .next()
+                .cloned()
+                .unwrap_or_default();
+            (messages, request)
+        };
+        let path = parts.uri.path().to_string();
+        let request_headers = header_values(&parts.headers);
+
+        let response = match self.send(parts, tokio_stream::iter(messages)).await {
+            Ok(response) => response,
+            Err(status) => return status.into_http(),
+        };
+
+        let (parts, body) = response.into_parts();
+        let collected = match body.collect().await {
+            Ok(collected) => collected,
+            Err(status) => return status.into_http(),
+        };
+        let trailers = collected.trailers().cloned().unwrap_or_default();
+        let data = collected.to_bytes();
+
+        // a trailers-only response carries the status in its headers
+        let status = Status::from_header_map(&trailers)
+            .or_else(|| Status::from_header_map(&parts.headers))
+            .unwrap_or_else(|| Status::unknown("missing grpc-status"));
+
+        recorder.record(Recording {
+            path,
+            request: RecordedRequest {
+                headers: request_headers,
+                body: STANDARD.encode(&request),
+            },
+            response: RecordedResponse {
+                status: status.code() as i32,
+                message: status.message().to_string(),
+                headers: header_values(&parts.headers),
+                messages: frames(&data).iter().map(|m| STANDARD.encode(m)).collect(),
+                trailers: header_values(&trailers),
+            },
+        });
+
+        let mut frames = vec![Ok::<_, Status>(http_body::Frame::data(data))];
+        if !trailers.is_empty() {
+            frames.push(Ok(http_body::Frame::trailers(trailers)));
+        }
+        http::Response::from_parts(
+            parts,
+            Body::new(StreamBody::new(tokio_stream::iter(frames))),
+        )
+    }
+
+    async fn send<S>(
+        &self,
+        parts: request::Parts,
+        messages: S,
+    ) -> Result<http::Response<Body>, Status>
+    where
+        S: Stream<Item = Result<Vec<u8>, Status>> + Send + 'static,
+    {
+        info!("Forwarding {} to the upstream server", parts.uri.path());
+
+        let body = EncodeBody::new_client(GenericCodec.encoder(), messages, None, None);
+        let request = http::Request::from_parts(parts, Body::new(body));
+
+        self.channel.clone().oneshot(request).await.map_err(|e| {
+            warn!("Unable to reach the upstream server: {e}");
+            Status::unavailable(format!("Unable to reach the upstream server: {e}"))
+        })
+    }
+}
+
+/// Splits a gRPC encoded body into its (uncompressed) messages.
+fn frames(mut data: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    while data.len() >= 5 {
+        let len = u32::from_be_bytes([data[1], data[2], data[3], data[4]]) as usize;
+        let Some(message) = data.get(5..5 + len) else {
+            break;
+        };
+        messages.push(message.to_vec());
+        data = &data[5 + len..];
+    }
+    messages
+}
+
+/// The values of each header, in a serializable form.
+pub(crate) fn header_values(map: &HeaderMap) -> BTreeMap<String, Vec<String>> {
+    let mut headers = BTreeMap::<String, Vec<String>>::new();
+    for (name, value) in map {
+        headers
+            .entry(name.to_string())
+            .or_default()
+            .push(String::from_utf8_lossy(value.as_bytes()).into_owned());
+    }
+    headers
+}
+
+/// Writes every request forwarded to the upstream server, and its response, to a file.
+#[derive(Debug, Clone)]
+pub(crate) struct Recorder {
+    path: PathBuf,
+    recordings: Arc<Mutex<Recordings>>,
+}
+
+impl Recorder {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            recordings: Arc::default(),
+        }
+    }
+
+    fn record(&self, recording: Recording) {
+        let mut recordings = self.recordings.lock().unwrap();
+        recordings.recordings.push(recording);
+
+        // the file is rewritten on each call so it is complete even if the test panics
+        if let Err(e) = recordings.save(&self.path) {
+            warn!(
+                "Unable to write the recordings to {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// The content of a recording file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Recordings {
+    recordings: Vec<Recording>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Recording {
+    path: String,
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+/// Binary values (messages) are base64 encoded.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedRequest {
+    headers: BTreeMap<String, Vec<String>>,
+    body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: i32,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    headers: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    messages: Vec<String>,
+    #[serde(default)]
+    trailers: BTreeMap<String, Vec<String>>,
+}
+
+impl Recording {
+    /// The headers, except the ones set by the mock server itself.
+    fn header_map(headers: &BTreeMap<String, Vec<String>>) -> io::Result<HeaderMap> {
+        let mut map = HeaderMap::new();
+        for (name, values) in headers {
+            if matches!(name.as_str(), "content-type" | "content-length" | "date")
+                || name.starts_with("grpc-") && !name.ends_with("-bin")
+            {
+                continue;
+            }
+
+            let name = HeaderName::try_from(name.as_str()).map_err(io::Error::other)?;
+            for value in values {
+                map.append(
+                    name.clone(),
+                    HeaderValue::try_from(value.as_str()).map_err(io::Error::other)?,
+                );
+            }
+        }
+        Ok(map)
+    }
+
+    fn decode(value: &str) -> io::Result<Vec<u8>> {
+        STANDARD.decode(value).map_err(io::Error::other)
+    }
+
+    /// A rule replying to the same request with the recorded response.
+    ///
+    /// Only the first message of a streaming response is replayed.
+    fn into_rule(self) -> io::Result<MockBuilder> {
+        let mut rule = MockBuilder::given(&self.path);
+        rule.request_body = Some(BodyMatcher::Exact(Self::decode(&self.request.body)?));
+        rule.status_code = Some(Code::from(self.response.status));
+        rule.status_message = Some(self.response.message).filter(|message| !message.is_empty());
+        rule.response_headers = Self::header_map(&self.response.headers)?;
+        rule.response_trailers = Self::header_map(&self.response.trailers)?;
+        rule.result = self
+            .response
+            .messages
+            .first()
+            .map(|m| Self::decode(m))
+            .transpose()?
+            .map(ResponseBody::fixed);
+
+        Ok(rule)
+    }
+}
+
+impl Recordings {
+    pub(crate) fn load(path: &Path) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        serde_json::from_reader(io::BufReader::new(file)).map_err(io::Error::other)
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    pub(crate) fn into_rules(self) -> io::Result<Vec<MockBuilder>> {
+        self.recordings
+            .into_iter()
+            .map(Recording::into_rule)
+            .collect()
+    }
+}
@@ -0,0 +1,73 @@
+//! Response bodies corrupted on purpose, for [`Then::return_raw_frame`](crate::Then::return_raw_frame).
+//!
+//! A gRPC message is sent as a frame: a compressed flag (1 byte), the length of the message
+//! (4 bytes, big endian), then the encoded message. These presets break one of them:
+//! ```no_run
+//! # use wiremock_grpc::*;
+//! # #[derive(Clone, PartialEq, prost::Message)]
+//! # struct HelloReply { #[prost(string, tag = "1")] message: String }
+//! MockBuilder::given("/hello.Greeter/SayHello")
+//!     .return_raw_frame(raw_frame::truncated(HelloReply { message: "Hello".into() }));
+//! ```
+
+/// Default maximum size of a message decoded by a tonic client (4 MiB).
+pub const DEFAULT_MAX_DECODING_SIZE: usize = 4 * 1024 * 1024;
+
+/// A well-formed frame holding the already encoded `message`, with the compressed flag set
+/// when `compressed`.
+pub fn frame(compressed: bool, message: &[u8]) -> Vec<u8> {
+    let len = u32::try_from(message.len()).expect("The message is too large for a frame");
+    let mut frame = Vec::with_capacity(5 + message.len());
+    frame.push(u8::from(compressed));
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(message);
+    frame
+}
+
+/// The length prefix announces the whole message, only the first half is sent.
+pub fn truncated<T: prost::Message>(message: T) -> Vec<u8> {
+    let mut frame = frame(false, &message.encode_to_vec());
+    let message_len = frame.len() - 5;
+    frame.truncate(5 + message_len / 2);
+    frame
+}
+
+/// The length prefix announces half of the message: the client decodes the first half, then
+/// reads the rest as the next frame.
+pub fn wrong_length_prefix<T: prost::Message>(message: T) -> Vec<u8> {
+    let mut frame = frame(false, &message.encode_to_vec());
+    let half = u32::try_from((frame.len() - 5) / 2).unwrap_or(u32::MAX);
+    frame[1..5].copy_from_slice(&half.to_be_bytes());
+    frame
+}
+
+/// The compressed flag is set, while the message is not compressed and the response has no
+/// `grpc-encoding` header.
+pub fn compressed_flag_without_compression<T: prost::Message>(message: T) -> Vec<u8> {
+    frame(true, &message.encode_to_vec())
+}
+
+/// A frame whose message uses an invalid wire type (`7`), which no message can be decoded from.
+pub fn invalid_wire_type() -> Vec<u8> {
+    // field 1, wire type 7
+    frame(false, &[(1 << 3) | 7, 0])
+}
+
+/// A valid message of `size` bytes (a single bytes field), eg. one byte over
+/// [`DEFAULT_MAX_DECODING_SIZE`] to exceed the client's limit.
+///
+/// ## Panics
+/// * When `size` is less than 6 bytes, the size of the smallest message built this way.
+pub fn oversized(size: usize) -> Vec<u8> {
+    assert!(size >= 6, "An oversized message is at least 6 bytes");
+
+    // field 1, length delimited, with its length as a 5 bytes varint
+    let len = size - 6;
+    let mut message = vec![(1 << 3) | 2];
+    for shift in (0..35).step_by(7) {
+        let byte = ((len >> shift) & 0x7f) as u8;
+        message.push(if shift < 28 { byte | 0x80 } else { byte });
+    }
+    message.resize(size, b'a');
+    frame(false, &message)
+}
@@ -0,0 +1,203 @@
+//! One mock server for every test of a process, see [`SharedServer`].
+
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tonic::{
+    codegen::http::{HeaderMap, Uri},
+    transport::{Channel, Endpoint},
+};
+
+use crate::wiremock::{
+    grpc_server::RequestItem,
+    matching::{GrpcRequest, Match, Matcher},
+    transport::reachable,
+};
+use crate::{GrpcServer, MockBuilder};
+
+/// The metadata naming the namespace of a request, for the clients not using
+/// [`SharedServer::channel`].
+pub const NAMESPACE_HEADER: &str = "x-wiremock-namespace";
+
+/// The channels of [`SharedServer::channel`] send the namespace as the host of the requests,
+/// eg. `test-1.wiremock`.
+const NAMESPACE_DOMAIN: &str = ".wiremock";
+
+/// A test's view of a server shared by the whole process, given by the generated `shared()`.
+///
+/// Starting one server per test is slow with many tests; instead each handle has its own
+/// namespace on one server: it only matches the requests sent with its
+/// [`channel`](Self::channel) (or with the [`NAMESPACE_HEADER`] metadata), only sees these
+/// requests, and only verifies its own rules. They are removed when the handle is dropped.
+/// ```no_run
+/// # wiremock_grpc::generate_svc! { package hello; service Greeter { SayHello, } }
+/// # use wiremock_grpc::*;
+/// # async fn example() {
+/// let server = GreeterMockServer::shared();
+/// server.setup(MockBuilder::when().path_say_hello().then().return_status(tonic::Code::Ok));
+///
+/// let channel = server.channel().await;
+/// # }
+/// ```
+///
+/// ## Panics
+/// * When dropped with rules that were never matched, like [`GrpcServer`].
+#[derive(Debug)]
+pub struct SharedServer {
+    server: &'static GrpcServer,
+    namespace: String,
+    /// The ids of the rules set up through this handle.
+    rules: Mutex<Vec<u64>>,
+}
+
+impl SharedServer {
+    /// A handle with a new namespace on `server`.
+    pub fn new(server: &'static GrpcServer) -> Self {
+        static NEXT_NAMESPACE: AtomicU64 = AtomicU64::new(1);
+
+        Self {
+            server,
+            namespace: format!("test-{}", NEXT_NAMESPACE.fetch_add(1, Ordering::Relaxed)),
+            rules: Mutex::default(),
+        }
+    }
+
+    /// Identifies the requests of this handle, eg. `test-1`.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn address(&self) -> &SocketAddr {
+        self.server.address()
+    }
+
+    /// A channel connected to the server, its requests are in the namespace of this handle.
+    ///
+    /// ## Panics
+    /// * When unable to connect to the server.
+    pub async fn channel(&self) -> Channel {
+        let origin: Uri = format!("http://{}{NAMESPACE_DOMAIN}", self.namespace)
+            .parse()
+            .expect("Invalid namespace");
+        Endpoint::from_shared(format!("http://{}", reachable(*self.address())))
+            .map(|endpoint| endpoint.origin(origin))
+            .expect("Invalid address")
+            .connect()
+            .await
+            .expect("Unable to connect to the mock server")
+    }
+
+    /// Set up `rule` for the requests of this namespace only, see [`GrpcServer::setup`].
+    pub fn setup(&self, rule: impl Into<MockBuilder>) -> MockBuilder {
+        let mut rule: MockBuilder = rule.into();
+        rule.request_matchers
+            .push(Matcher(Arc::new(Namespace(self.namespace.clone()))));
+
+        let rule = self.server.add_rule(rule);
+        self.rules.lock().unwrap().push(rule.id);
+        rule
+    }
+
+    /// See [`GrpcServer::find`], among the rules of this handle.
+    pub fn find(&self, r: &MockBuilder) -> Option<Vec<RequestItem>> {
+        let ids = self.rules.lock().unwrap();
+        self.server
+            .rules
+            .read()
+            .unwrap()
+            .iter()
+            .find(|item| ids.contains(&item.id) && &item.rule == r)
+            .map(|item| item.invocations.clone())
+    }
+
+    /// See [`GrpcServer::find_one`], among the rules of this handle.
+    ///
+    /// ## Panics
+    /// * When there is not exactly one request matching `r`.
+    /// * When `r` was not set up with this handle.
+    pub fn find_one(&self, r: &MockBuilder) -> RequestItem {
+        match self.find(r).as_deref() {
+            Some([request]) => request.clone(),
+            Some([]) => panic!("No request maching the given criteria."),
+            Some(_) => panic!("More then one request matching the criteria."),
+            None => panic!("The given MockBuilder is not registered with the mock server."),
+        }
+    }
+
+    /// The requests received in this namespace, in order.
+    pub fn journal(&self) -> Vec<RequestItem> {
+        self.server
+            .journal
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|request| self.contains(request))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes the rules and the requests of this namespace.
+    pub fn reset(&self) {
+        self.remove();
+    }
+
+    /// Removes the rules and the requests of this namespace, along with the requests sent
+    /// outside of any namespace, and returns the paths of the rules never matched.
+    fn remove(&self) -> Vec<String> {
+        let ids = std::mem::take(&mut *self.rules.lock().unwrap());
+        let mut unmatched = Vec::new();
+        self.server.rules.write().unwrap().retain(|item| {
+            let own = ids.contains(&item.id);
+            if own && item.invocations_count == 0 {
+                unmatched.push(item.rule.path.clone());
+            }
+            !own
+        });
+        self.server
+            .journal
+            .write()
+            .unwrap()
+            .retain(|request| request.namespace.is_some() && !self.contains(request));
+        unmatched
+    }
+
+    fn contains(&self, request: &RequestItem) -> bool {
+        request.namespace.as_deref() == Some(self.namespace.as_str())
+    }
+}
+
+impl Drop for SharedServer {
+    fn drop(&mut self) {
+        let unmatched = self.remove();
+        // do not abort the tests already failing
+        if !unmatched.is_empty() && !std::thread::panicking() {
+            panic!(
+                "Namespace {} dropped with unmatched rules: \n{}",
+                self.namespace,
+                unmatched.join("\n")
+            );
+        }
+    }
+}
+
+/// The namespace of a request, from [`NAMESPACE_HEADER`] or else from its host.
+pub(crate) fn namespace<'a>(uri: &'a Uri, headers: &'a HeaderMap) -> Option<&'a str> {
+    match headers.get(NAMESPACE_HEADER) {
+        Some(value) => value.to_str().ok(),
+        None => uri.host()?.strip_suffix(NAMESPACE_DOMAIN),
+    }
+}
+
+/// Matches the requests of a namespace.
+struct Namespace(String);
+
+impl Match for Namespace {
+    fn matches(&self, request: &GrpcRequest<'_>) -> bool {
+        namespace(request.uri(), request.headers()) == Some(self.0.as_str())
+    }
+}
@@ -1,19 +1,11 @@
-use prost::{bytes::BufMut, Message};
+use std::time::Duration;
 
-use tonic::{codec::Codec, Code};
+use prost::bytes::{Buf, BufMut};
 
-pub(crate) struct GenericSvc(pub(crate) Vec<u8>);
-impl tonic::server::UnaryService<Vec<u8>> for GenericSvc {
-    type Response = Vec<u8>;
-    type Future = tonic::codegen::BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-    fn call(&mut self, _: tonic::Request<Vec<u8>>) -> Self::Future {
-        let body = self.0.clone();
-        let fut = async move { Ok(tonic::Response::new(body)) };
-
-        Box::pin(fut)
-    }
-}
+use tonic::codec::{Codec, CompressionEncoding};
+use tonic::codegen::http::HeaderMap;
 
+/// A [`Codec`] passing the already encoded messages through as bytes.
 #[derive(Default)]
 pub(crate) struct GenericCodec;
 
@@ -55,7 +47,7 @@ impl tonic::codec::Encoder for GenericProstEncoder {
     }
 }
 
-/// A [`Decoder`] that knows how to decode `U`.
+/// A [`Decoder`] that returns the raw bytes of each message.
 #[derive(Debug, Clone, Default)]
 pub struct GenericProstDecoder;
 
@@ -67,16 +59,67 @@ impl tonic::codec::Decoder for GenericProstDecoder {
         &mut self,
         buf: &mut tonic::codec::DecodeBuf<'_>,
     ) -> Result<Option<Self::Item>, Self::Error> {
-        let item = Message::decode(buf)
-            .map(Option::Some)
-            .map_err(from_decode_error)?;
+        // the buffer holds exactly one message, the type is not known here
+        // so it is kept encoded.
+        let item = buf.copy_to_bytes(buf.remaining()).to_vec();
+
+        Ok(Some(item))
+    }
+}
+
+/// The timeout of the `grpc-timeout` header (eg. `100m` for 100 milliseconds), [`None`] when
+/// missing or invalid.
+pub(crate) fn grpc_timeout(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("grpc-timeout")?.to_str().ok()?;
+    if value.is_empty() || value.len() > 9 || !value.is_char_boundary(value.len() - 1) {
+        return None;
+    }
+
+    let (amount, unit) = value.split_at(value.len() - 1);
+    let amount: u64 = amount.parse().ok()?;
+    Some(match unit {
+        "H" => Duration::from_secs(amount * 60 * 60),
+        "M" => Duration::from_secs(amount * 60),
+        "S" => Duration::from_secs(amount),
+        "m" => Duration::from_millis(amount),
+        "u" => Duration::from_micros(amount),
+        "n" => Duration::from_nanos(amount),
+        _ => return None,
+    })
+}
+
+/// The encoding named in a `grpc-encoding` header, [`None`] for `identity`.
+///
+/// Returns the name back when the encoding is not supported.
+pub(crate) fn compression_encoding(name: &str) -> Result<Option<CompressionEncoding>, &str> {
+    match name.trim() {
+        "identity" => Ok(None),
+        #[cfg(feature = "gzip")]
+        "gzip" => Ok(Some(CompressionEncoding::Gzip)),
+        #[cfg(feature = "compression")]
+        "deflate" => Ok(Some(CompressionEncoding::Deflate)),
+        #[cfg(feature = "compression")]
+        "zstd" => Ok(Some(CompressionEncoding::Zstd)),
+        _ => Err(name),
+    }
+}
 
-        Ok(item)
+/// The feature accepting the requests compressed with `name`, when it is not enabled.
+pub(crate) fn missing_feature(name: &str) -> Option<&'static str> {
+    match name.trim() {
+        "gzip" => Some("gzip"),
+        "deflate" | "zstd" => Some("compression"),
+        _ => None,
     }
 }
 
-fn from_decode_error(error: prost::DecodeError) -> tonic::Status {
-    // Map Protobuf parse errors to an INTERNAL status code, as per
-    // https://github.com/grpc/grpc/blob/master/doc/statuscodes.md
-    tonic::Status::new(Code::Internal, error.to_string())
+/// The supported encodings listed in the `grpc-accept-encoding` header.
+pub(crate) fn accept_encodings(headers: &HeaderMap) -> Vec<CompressionEncoding> {
+    headers
+        .get_all("grpc-accept-encoding")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| compression_encoding(name).ok().flatten())
+        .collect()
 }
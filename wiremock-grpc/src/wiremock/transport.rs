@@ -0,0 +1,201 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Arc, Mutex},
+};
+
+use hyper_util::rt::TokioIo;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, DuplexStream},
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+use tonic::{
+    codegen::{tokio_stream, tokio_stream::StreamExt},
+    service::Routes,
+    transport::{
+        server::{Connected, Router, TcpIncoming},
+        Channel, Endpoint, Server,
+    },
+};
+
+use crate::wiremock::fault::FaultyIo;
+
+/// Size of the in-memory pipe used by [`Transport::InMemory`].
+const IN_MEMORY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// How a [`GrpcServer`](crate::GrpcServer) accepts connections.
+#[derive(Debug, Clone)]
+pub(crate) enum Transport {
+    Tcp,
+    #[cfg(feature = "tls")]
+    Tls {
+        certificate: Vec<u8>,
+        key: Vec<u8>,
+    },
+    #[cfg(unix)]
+    Uds(std::path::PathBuf),
+    InMemory {
+        connections: UnboundedSender<DuplexStream>,
+        incoming: Arc<Mutex<Option<UnboundedReceiver<DuplexStream>>>>,
+    },
+}
+
+pub(crate) type ServerHandle = tokio::task::JoinHandle<Result<(), tonic::transport::Error>>;
+
+impl Transport {
+    pub(crate) fn in_memory() -> Self {
+        let (connections, incoming) = mpsc::unbounded_channel();
+        Transport::InMemory {
+            connections,
+            incoming: Arc::new(Mutex::new(Some(incoming))),
+        }
+    }
+
+    /// Whether the server can be reached using `address`.
+    pub(crate) fn is_network(&self) -> bool {
+        match self {
+            Transport::Tcp => true,
+            #[cfg(feature = "tls")]
+            Transport::Tls { .. } => true,
+            #[cfg(unix)]
+            Transport::Uds(_) => false,
+            Transport::InMemory { .. } => false,
+        }
+    }
+
+    /// `http1`: accept HTTP/1.1 connections (gRPC-Web) along with HTTP/2.
+    ///
+    /// `faults`: wrap every connection in a [`FaultyIo`], for the rules to break it.
+    ///
+    /// ## Panics
+    /// * When unable to listen to the socket of [`Transport::Uds`].
+    pub(crate) fn serve(
+        &self,
+        address: SocketAddr,
+        routes: Routes,
+        http1: bool,
+        faults: bool,
+    ) -> ServerHandle {
+        let mut server = Server::builder().accept_http1(http1);
+
+        match self {
+            Transport::Tcp => serve_tcp(server.add_routes(routes), address, faults),
+            #[cfg(feature = "tls")]
+            Transport::Tls { certificate, key } => {
+                let tls = tonic::transport::ServerTlsConfig::new()
+                    .identity(tonic::transport::Identity::from_pem(certificate, key));
+                let mut server = server.tls_config(tls).expect("Invalid TLS configuration");
+                serve_tcp(server.add_routes(routes), address, faults)
+            }
+            #[cfg(unix)]
+            Transport::Uds(path) => {
+                let listener = tokio::net::UnixListener::bind(path).unwrap_or_else(|e| {
+                    panic!("Unable to bind to {}: {e}", path.display());
+                });
+                let incoming = tokio_stream::wrappers::UnixListenerStream::new(listener);
+                serve_incoming(server.add_routes(routes), incoming, faults)
+            }
+            Transport::InMemory { incoming, .. } => {
+                let incoming = incoming
+                    .lock()
+                    .unwrap()
+                    .take()
+                    .expect("The in-memory server is already started");
+                let incoming = tokio_stream::wrappers::UnboundedReceiverStream::new(incoming)
+                    .map(Ok::<_, std::io::Error>);
+                serve_incoming(server.add_routes(routes), incoming, faults)
+            }
+        }
+    }
+
+    pub(crate) async fn channel(
+        &self,
+        address: SocketAddr,
+    ) -> Result<Channel, tonic::transport::Error> {
+        let address = reachable(address);
+
+        match self {
+            Transport::Tcp => {
+                Endpoint::from_shared(format!("http://{address}"))?
+                    .connect()
+                    .await
+            }
+            #[cfg(feature = "tls")]
+            Transport::Tls { certificate, .. } => {
+                let tls = tonic::transport::ClientTlsConfig::new()
+                    .ca_certificate(tonic::transport::Certificate::from_pem(certificate))
+                    .domain_name("localhost");
+                Endpoint::from_shared(format!("https://{address}"))?
+                    .tls_config(tls)?
+                    .connect()
+                    .await
+            }
+            #[cfg(unix)]
+            Transport::Uds(path) => {
+                Endpoint::from_shared(format!("unix://{}", path.display()))?
+                    .connect()
+                    .await
+            }
+            Transport::InMemory { connections, .. } => {
+                let connections = connections.clone();
+                // the uri is required but never resolved
+                Endpoint::from_static("http://in-memory")
+                    .connect_with_connector(tower::service_fn(move |_| {
+                        let (client, server) = tokio::io::duplex(IN_MEMORY_BUFFER_SIZE);
+                        let sent = connections.send(server);
+                        async move {
+                            sent.map_err(|_| {
+                                std::io::Error::new(
+                                    std::io::ErrorKind::ConnectionRefused,
+                                    "the in-memory server is not running",
+                                )
+                            })?;
+                            Ok::<_, std::io::Error>(TokioIo::new(client))
+                        }
+                    }))
+                    .await
+            }
+        }
+    }
+}
+
+/// A server listening to all interfaces is reached through the loopback.
+pub(crate) fn reachable(address: SocketAddr) -> SocketAddr {
+    match address.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => {
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), address.port())
+        }
+        IpAddr::V6(ip) if ip.is_unspecified() => {
+            SocketAddr::new(Ipv6Addr::LOCALHOST.into(), address.port())
+        }
+        _ => address,
+    }
+}
+
+/// An error binding `address` comes back through the task, as with [`Router::serve`].
+fn serve_tcp(router: Router, address: SocketAddr, faults: bool) -> ServerHandle {
+    if !faults {
+        return tokio::spawn(router.serve(address));
+    }
+    match TcpIncoming::bind(address) {
+        Ok(incoming) => serve_incoming(router, incoming.with_nodelay(Some(true)), true),
+        // binding again fails the same way, returning the error
+        Err(_) => tokio::spawn(router.serve(address)),
+    }
+}
+
+fn serve_incoming<IO>(
+    router: Router,
+    incoming: impl tokio_stream::Stream<Item = std::io::Result<IO>> + Send + 'static,
+    faults: bool,
+) -> ServerHandle
+where
+    IO: AsyncRead + AsyncWrite + Connected + Unpin + Send + 'static,
+    IO::ConnectInfo: Clone + Send + Sync + 'static,
+{
+    if faults {
+        let incoming = incoming.map(|io| io.map(FaultyIo::new));
+        tokio::spawn(router.serve_with_incoming(incoming))
+    } else {
+        tokio::spawn(router.serve_with_incoming(incoming))
+    }
+}
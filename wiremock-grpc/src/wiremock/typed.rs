@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+
+use crate::wiremock::builder::{ThenBuilder, WhenBuilder};
+use crate::{GrpcServer, MockBuilder, Mountable, Then};
+use tonic::codegen::http::header::IntoHeaderName;
+use tonic::codegen::http::HeaderValue;
+
+/// A [`WhenBuilder`] bound to the request (`Req`) and response (`Resp`) types of a single rpc.
+///
+/// Returned by the `path_*` methods generated by [`generate_svc!`](crate::generate_svc) for rpc
+/// declared with their types, eg. `SayHello(HelloRequest) -> HelloReply`.
+pub struct TypedWhenBuilder<Req, Resp> {
+    inner: WhenBuilder,
+    _types: PhantomData<fn(Req) -> Resp>,
+}
+
+/// A [`ThenBuilder`] that only accepts `Resp` as the response body.
+pub struct TypedThenBuilder<Req, Resp> {
+    inner: ThenBuilder,
+    _types: PhantomData<fn(Req) -> Resp>,
+}
+
+impl<Req, Resp> From<WhenBuilder> for TypedWhenBuilder<Req, Resp> {
+    fn from(inner: WhenBuilder) -> Self {
+        Self {
+            inner,
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<Req, Resp> Clone for TypedWhenBuilder<Req, Resp> {
+    fn clone(&self) -> Self {
+        self.inner.clone().into()
+    }
+}
+
+impl<Req, Resp> Clone for TypedThenBuilder<Req, Resp> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<Req, Resp> TypedWhenBuilder<Req, Resp>
+where
+    Req: prost::Message,
+    Resp: prost::Message,
+{
+    pub fn header<K, V>(self, key: K, value: V) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self.inner.header(key, value).into()
+    }
+
+    /// See [`WhenBuilder::matching`].
+    pub fn matching(self, matcher: impl crate::Match) -> Self {
+        self.inner.matching(matcher).into()
+    }
+
+    /// See [`WhenBuilder::header_exists`].
+    pub fn header_exists<K: IntoHeaderName>(self, key: K) -> Self {
+        self.inner.header_exists(key).into()
+    }
+
+    /// See [`WhenBuilder::header_missing`].
+    pub fn header_missing<K: IntoHeaderName>(self, key: K) -> Self {
+        self.inner.header_missing(key).into()
+    }
+
+    /// See [`WhenBuilder::header_prefix`].
+    pub fn header_prefix<K: IntoHeaderName>(self, key: K, prefix: &str) -> Self {
+        self.inner.header_prefix(key, prefix).into()
+    }
+
+    /// See [`WhenBuilder::header_eq_ignore_case`].
+    pub fn header_eq_ignore_case<K: IntoHeaderName>(self, key: K, value: &str) -> Self {
+        self.inner.header_eq_ignore_case(key, value).into()
+    }
+
+    /// See [`WhenBuilder::header_regex`].
+    pub fn header_regex<K: IntoHeaderName>(self, key: K, pattern: &str) -> Self {
+        self.inner.header_regex(key, pattern).into()
+    }
+
+    /// See [`WhenBuilder::header_values`].
+    pub fn header_values<K, V>(self, key: K, values: impl IntoIterator<Item = V>) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self.inner.header_values(key, values).into()
+    }
+
+    /// See [`WhenBuilder::header_bin`].
+    pub fn header_bin<K: IntoHeaderName>(self, key: K, value: impl Into<Vec<u8>>) -> Self {
+        self.inner.header_bin(key, value).into()
+    }
+
+    /// Match requests whose message is exactly equal to `message`.
+    pub fn body_eq(self, message: Req) -> Self {
+        self.inner.body_eq(message).into()
+    }
+
+    /// Match requests containing every field set in `message`, see [`WhenBuilder::body_contains`].
+    pub fn body_contains(self, message: Req) -> Self {
+        self.inner.body_contains(message).into()
+    }
+
+    /// See [`WhenBuilder::deadline_at_most`].
+    pub fn deadline_at_most(self, timeout: std::time::Duration) -> Self {
+        self.inner.deadline_at_most(timeout).into()
+    }
+
+    pub fn then(&self) -> TypedThenBuilder<Req, Resp> {
+        TypedThenBuilder {
+            inner: self.inner.then(),
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<Req, Resp> TypedThenBuilder<Req, Resp>
+where
+    Req: prost::Message,
+    Resp: prost::Message,
+{
+    pub fn return_status(self, status: tonic::Code) -> Self {
+        self.map(|b| b.return_status(status))
+    }
+
+    /// See [`Then::return_body`].
+    pub fn return_body<F>(self, f: F) -> Self
+    where
+        F: Fn() -> Resp,
+    {
+        self.map(|b| b.return_body(f))
+    }
+
+    /// See [`Then::return_body_with`].
+    pub fn return_body_with<F>(self, f: F) -> Self
+    where
+        F: Fn() -> Resp + Send + Sync + 'static,
+    {
+        self.map(|b| b.return_body_with(f))
+    }
+
+    pub fn return_header<K, V>(self, key: K, value: V) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self.map(|b| b.return_header(key, value))
+    }
+
+    pub fn return_trailer<K, V>(self, key: K, value: V) -> Self
+    where
+        K: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+        <V as TryInto<HeaderValue>>::Error: std::fmt::Debug,
+    {
+        self.map(|b| b.return_trailer(key, value))
+    }
+
+    pub fn with_delay(self, delay: std::time::Duration) -> Self {
+        self.map(|b| b.with_delay(delay))
+    }
+
+    /// See [`Then::return_raw_frame`].
+    pub fn return_raw_frame(self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.map(|b| b.return_raw_frame(bytes))
+    }
+
+    /// See [`Then::fault`].
+    pub fn fault(self, fault: crate::Fault) -> Self {
+        self.map(|b| b.fault(fault))
+    }
+
+    /// See [`Then::compress_with`].
+    #[cfg(feature = "gzip")]
+    pub fn compress_with(self, encoding: tonic::codec::CompressionEncoding) -> Self {
+        self.map(|b| b.compress_with(encoding))
+    }
+
+    fn map(self, f: impl FnOnce(ThenBuilder) -> ThenBuilder) -> Self {
+        Self {
+            inner: f(self.inner),
+            _types: PhantomData,
+        }
+    }
+}
+
+#[allow(clippy::from_over_into)]
+impl<Req, Resp> Into<MockBuilder> for TypedThenBuilder<Req, Resp> {
+    fn into(self) -> MockBuilder {
+        self.inner.into()
+    }
+}
+
+impl<Req, Resp> Mountable for TypedThenBuilder<Req, Resp> {
+    fn mount(self, s: &mut GrpcServer) {
+        self.inner.mount(s);
+    }
+}
@@ -0,0 +1,187 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+};
+
+use hello::{greeter_client::GreeterClient, HelloRequest};
+use serde_json::{json, Value};
+use tonic::Code;
+use wiremock_grpc::{prost_reflect::DescriptorPool, GrpcServer};
+
+const DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/hello.bin"));
+
+/// A minimal HTTP/1.1 client: returns the status code and the JSON body.
+async fn http(admin: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    let request = {
+        let body = body.map(|b| b.to_string()).unwrap_or_default();
+        format!(
+            "{method} {path} HTTP/1.1\r\nHost: {admin}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    };
+
+    let response = tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect(admin).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    })
+    .await
+    .unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    let body = serde_json::from_str(body).unwrap_or(Value::Null);
+    (status, body)
+}
+
+async fn start() -> (GrpcServer, SocketAddr) {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let server = GrpcServer::new(port).start().await;
+
+    let pool = DescriptorPool::decode(DESCRIPTOR_SET).unwrap();
+    let admin = server
+        .start_admin("127.0.0.1:0".parse().unwrap(), pool)
+        .await
+        .unwrap();
+    (server, admin)
+}
+
+#[tokio::test]
+async fn configures_the_server() {
+    let (server, admin) = start().await;
+    let mut client = GreeterClient::new(server.channel().await);
+
+    let (status, body) = http(
+        admin,
+        "POST",
+        "/__admin/mappings",
+        Some(json!({
+            "request": {
+                "path": "/hello.Greeter/SayHello",
+                "body": { "equalTo": { "name": "Alice" } }
+            },
+            "response": { "body": { "message": "Hello Alice" } }
+        })),
+    )
+    .await;
+    assert_eq!(201, status);
+    let id = body["id"].as_u64().unwrap();
+    assert_eq!(1, server.rules_len());
+
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Hello Alice", response.into_inner().message);
+
+    let (_, body) = http(admin, "GET", "/__admin/mappings", None).await;
+    assert_eq!(id, body["mappings"][0]["id"]);
+    assert_eq!(1, body["mappings"][0]["invocations"]);
+    assert_eq!(
+        "/hello.Greeter/SayHello",
+        body["mappings"][0]["mapping"]["request"]["path"]
+    );
+
+    let (_, body) = http(admin, "GET", "/__admin/requests", None).await;
+    assert_eq!("/hello.Greeter/SayHello", body["requests"][0]["path"]);
+    assert_eq!("Alice", body["requests"][0]["body"]["name"]);
+
+    let status = http(admin, "DELETE", &format!("/__admin/mappings/{id}"), None)
+        .await
+        .0;
+    assert_eq!(204, status);
+    assert_eq!(0, server.rules_len());
+
+    let status = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+
+    let status = http(admin, "DELETE", &format!("/__admin/mappings/{id}"), None)
+        .await
+        .0;
+    assert_eq!(404, status);
+
+    assert_eq!(204, http(admin, "POST", "/__admin/reset", None).await.0);
+    assert!(server.journal().is_empty());
+}
+
+#[tokio::test]
+async fn verifies_the_requests() {
+    let (server, admin) = start().await;
+    let mut client = GreeterClient::new(server.channel().await);
+
+    for name in ["Alice", "Alice", "Bob"] {
+        let _ = client.say_hello(HelloRequest { name: name.into() }).await;
+    }
+
+    let alice = json!({
+        "path": "/hello.Greeter/SayHello",
+        "body": { "contains": { "name": "Alice" } }
+    });
+    let (status, body) = http(
+        admin,
+        "POST",
+        "/__admin/verify",
+        Some(json!({ "request": alice })),
+    )
+    .await;
+    assert_eq!((200, json!({ "count": 2 })), (status, body));
+
+    let (status, body) = http(
+        admin,
+        "POST",
+        "/__admin/verify",
+        Some(json!({ "request": alice, "count": 1 })),
+    )
+    .await;
+    assert_eq!((409, json!({ "count": 2 })), (status, body));
+
+    let status = http(
+        admin,
+        "POST",
+        "/__admin/verify",
+        Some(json!({ "request": { "path": "/hello.Greeter/WeatherInfo" } })),
+    )
+    .await
+    .0;
+    assert_eq!(409, status);
+}
+
+#[tokio::test]
+async fn rejects_invalid_mappings() {
+    let (server, admin) = start().await;
+
+    let (status, body) = http(
+        admin,
+        "POST",
+        "/__admin/mappings",
+        Some(json!({ "request": { "path": "/hello.Greeter/SayGoodbye" } })),
+    )
+    .await;
+    assert_eq!(400, status);
+    assert!(body["error"].as_str().unwrap().contains("SayGoodbye"));
+    assert_eq!(0, server.rules_len());
+}
+
+#[tokio::test]
+async fn stops_with_the_server() {
+    let (server, admin) = start().await;
+    drop(server);
+
+    // the aborted task releases the port once the runtime polls it
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(TcpStream::connect(admin).is_err());
+}
@@ -0,0 +1,86 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use std::net::TcpStream;
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::transport::Channel;
+use wiremock_grpc::*;
+
+/// A blocking client, like the wrappers under test, running on a runtime of its own.
+fn say_hello(channel: Channel, name: &str) -> String {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        GreeterClient::new(channel)
+            .say_hello(HelloRequest { name: name.into() })
+            .await
+            .unwrap()
+            .into_inner()
+            .message
+    })
+}
+
+fn start() -> (BlockingServer<GreeterMockServer>, MockBuilder) {
+    let mut server = GreeterMockServer::start_blocking();
+    let rule =
+        server.setup(
+            MockBuilder::when()
+                .path_say_hello()
+                .then()
+                .return_body(|| HelloReply {
+                    message: "Hello".into(),
+                }),
+        );
+    (server, rule)
+}
+
+#[test]
+fn without_async_runtime() {
+    let (server, rule) = start();
+
+    assert_eq!("Hello", say_hello(server.channel(), "Alice"));
+
+    let request = server.find_one(&rule);
+    assert_eq!("Alice", request.decode_body::<HelloRequest>().unwrap().name);
+}
+
+#[test]
+fn block_on_the_server_runtime() {
+    let (server, _) = start();
+
+    let channel = server.channel();
+    let reply = server.block_on(async {
+        GreeterClient::new(channel)
+            .say_hello(HelloRequest::default())
+            .await
+    });
+    assert_eq!("Hello", reply.unwrap().into_inner().message);
+}
+
+#[test]
+fn stops_on_drop() {
+    let (server, _) = start();
+    say_hello(server.channel(), "Alice");
+    let address = *server.address();
+
+    drop(server);
+    assert!(TcpStream::connect(address).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Rules never matched")]
+fn assert_all_matched() {
+    let (server, _) = start();
+    server.assert_all_matched();
+}
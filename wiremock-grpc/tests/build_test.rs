@@ -0,0 +1,86 @@
+mod hello {
+    tonic::include_proto!("hello");
+    wiremock_grpc::include_mock!("hello");
+
+    pub mod extended {
+        tonic::include_proto!("hello.extended");
+        wiremock_grpc::include_mock!("hello.extended");
+    }
+}
+
+use hello::{
+    greeter_client::GreeterClient, GreeterTypeSafeExt, HelloReply, HelloRequest, WeatherReply,
+    WeatherRequest,
+};
+use wiremock_grpc::MockBuilder;
+
+#[tokio::test]
+async fn generated_mock_server() {
+    let mut server = hello::GreeterMockServer::start_default().await;
+
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .body_eq(HelloRequest {
+                name: "Build".into(),
+            })
+            .then()
+            .return_body(|| HelloReply {
+                message: "Generated at build time".into(),
+            }),
+    );
+    server.setup(
+        MockBuilder::when()
+            .path_weather_info()
+            .then()
+            .return_body(|| WeatherReply {
+                weather: "Foggy".into(),
+            }),
+    );
+
+    let mut client = server.client(GreeterClient::new).await;
+
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Build".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Generated at build time", response.into_inner().message);
+
+    let response = client
+        .weather_info(WeatherRequest {
+            city: "London".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Foggy", response.into_inner().weather);
+}
+
+mod nested_package {
+    use super::hello::extended::{
+        extended_greeter_client::ExtendedGreeterClient, ExtendedGreeterTypeSafeExt,
+    };
+    use wiremock_grpc::MockBuilder;
+
+    #[tokio::test]
+    async fn generated_mock_server_nested_package() {
+        let mut server = super::hello::extended::ExtendedGreeterMockServer::start_default().await;
+
+        server.setup(MockBuilder::when().path_say_hello().then().return_body(|| {
+            super::hello::extended::HelloReply {
+                message: "Nested".into(),
+            }
+        }));
+
+        let mut client = server.client(ExtendedGreeterClient::new).await;
+
+        let response = client
+            .say_hello(super::hello::extended::HelloRequest {
+                name: "Test".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!("Nested", response.into_inner().message);
+    }
+}
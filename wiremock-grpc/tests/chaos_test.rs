@@ -0,0 +1,141 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use std::time::{Duration, Instant};
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::{transport::Channel, Code};
+use wiremock_grpc::*;
+
+async fn start() -> (GreeterMockServer, GreeterClient<Channel>) {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello".into(),
+            }),
+    );
+    let client = GreeterClient::new(server.channel().await);
+    (server, client)
+}
+
+async fn say_hello(client: &mut GreeterClient<Channel>) -> Result<(), Code> {
+    client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .map(|_| ())
+        .map_err(|status| status.code())
+}
+
+/// The outcome of 20 calls with `config`, and the decisions found in the journal.
+async fn run(config: ChaosConfig) -> (Vec<Result<(), Code>>, Vec<Option<ChaosDecision>>) {
+    let (server, mut client) = start().await;
+    server.chaos(config);
+
+    let mut outcomes = Vec::new();
+    for _ in 0..20 {
+        outcomes.push(say_hello(&mut client).await);
+    }
+    let decisions = server.journal().iter().map(|r| r.chaos).collect();
+    (outcomes, decisions)
+}
+
+#[tokio::test]
+async fn same_seed_same_failures() {
+    let config = ChaosConfig {
+        error_rate: 0.5,
+        codes: vec![Code::Unavailable, Code::ResourceExhausted],
+        seed: 7,
+        ..Default::default()
+    };
+
+    let (outcomes, decisions) = run(config.clone()).await;
+    assert!(outcomes.iter().any(Result::is_ok));
+    assert!(outcomes.iter().any(Result::is_err));
+    for (outcome, decision) in outcomes.iter().zip(&decisions) {
+        assert_eq!(outcome.err(), decision.unwrap().code);
+    }
+
+    assert_eq!((outcomes, decisions), run(config).await);
+}
+
+#[tokio::test]
+async fn always_failing() {
+    let (outcomes, _) = run(ChaosConfig {
+        error_rate: 1.0,
+        codes: vec![Code::ResourceExhausted],
+        ..Default::default()
+    })
+    .await;
+
+    assert!(outcomes.iter().all(|o| *o == Err(Code::ResourceExhausted)));
+}
+
+#[tokio::test]
+async fn latency() {
+    let (server, mut client) = start().await;
+    server.chaos(ChaosConfig {
+        latency_distribution: LatencyDistribution::Fixed(Duration::from_millis(200)),
+        ..Default::default()
+    });
+
+    let started = Instant::now();
+    say_hello(&mut client).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    let decision = server.journal()[0].chaos.unwrap();
+    assert_eq!(Duration::from_millis(200), decision.latency);
+    assert_eq!(None, decision.code);
+}
+
+#[tokio::test]
+async fn uniform_latency_within_bounds() {
+    let min = Duration::from_millis(1);
+    let max = Duration::from_millis(5);
+    let (_, decisions) = run(ChaosConfig {
+        latency_distribution: LatencyDistribution::Uniform { min, max },
+        seed: 3,
+        ..Default::default()
+    })
+    .await;
+
+    for decision in decisions {
+        let latency = decision.unwrap().latency;
+        assert!(min <= latency && latency <= max, "{latency:?}");
+    }
+}
+
+#[tokio::test]
+async fn disabled() {
+    let (server, mut client) = start().await;
+    server.chaos(ChaosConfig {
+        error_rate: 1.0,
+        ..Default::default()
+    });
+    assert_eq!(Err(Code::Unavailable), say_hello(&mut client).await);
+
+    server.chaos(ChaosConfig::default());
+    say_hello(&mut client).await.unwrap();
+    assert_eq!(None, server.journal()[1].chaos);
+}
+
+#[test]
+#[should_panic(expected = "Invalid chaos error rate NaN")]
+fn nan_error_rate() {
+    GrpcServer::in_memory().chaos(ChaosConfig {
+        error_rate: f64::NAN,
+        ..Default::default()
+    });
+}
@@ -0,0 +1,124 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::{codec::CompressionEncoding, transport::Channel, Code};
+use wiremock_grpc::*;
+
+fn hello(name: &str) -> HelloRequest {
+    HelloRequest { name: name.into() }
+}
+
+async fn client(server: &GreeterMockServer) -> GreeterClient<Channel> {
+    GreeterClient::new(server.channel().await)
+}
+
+#[tokio::test]
+async fn accepts_compressed_requests() {
+    let mut server = GreeterMockServer::start_default().await;
+
+    for (name, encoding) in [
+        ("gzip", CompressionEncoding::Gzip),
+        ("zstd", CompressionEncoding::Zstd),
+    ] {
+        let rule = server.setup(
+            MockBuilder::when()
+                .path_say_hello()
+                .body_eq(hello(name))
+                .then()
+                .return_body(|| HelloReply {
+                    message: name.into(),
+                }),
+        );
+
+        let response = client(&server)
+            .await
+            .send_compressed(encoding)
+            .say_hello(hello(name))
+            .await
+            .unwrap();
+        assert_eq!(name, response.into_inner().message);
+
+        let request = server.find_one(&rule);
+        assert_eq!(Some(encoding), request.encoding);
+        assert!(request.accept_encoding.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn uncompressed_requests_have_no_encoding() {
+    let mut server = GreeterMockServer::start_default().await;
+    let rule = server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(HelloReply::default),
+    );
+
+    client(&server)
+        .await
+        .say_hello(hello("plain"))
+        .await
+        .unwrap();
+
+    assert_eq!(None, server.find_one(&rule).encoding);
+}
+
+#[tokio::test]
+async fn compresses_the_response() {
+    let mut server = GreeterMockServer::start_default().await;
+    let rule = server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(|| HelloReply {
+                message: "compressed".into(),
+            })
+            .compress_with(CompressionEncoding::Gzip),
+    );
+
+    let response = client(&server)
+        .await
+        .accept_compressed(CompressionEncoding::Gzip)
+        .accept_compressed(CompressionEncoding::Zstd)
+        .say_hello(hello("Alice"))
+        .await
+        .unwrap();
+    assert_eq!("gzip", response.metadata().get("grpc-encoding").unwrap());
+    assert_eq!("compressed", response.into_inner().message);
+
+    let request = server.find_one(&rule);
+    assert_eq!(
+        vec![CompressionEncoding::Gzip, CompressionEncoding::Zstd],
+        request.accept_encoding
+    );
+}
+
+#[tokio::test]
+async fn compresses_the_response_even_when_not_accepted() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(|| HelloReply {
+                message: "compressed".into(),
+            })
+            .compress_with(CompressionEncoding::Zstd),
+    );
+
+    let status = client(&server)
+        .await
+        .say_hello(hello("Alice"))
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
@@ -0,0 +1,127 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use std::time::{Duration, Instant};
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::{Code, Request};
+use wiremock_grpc::*;
+
+fn hello() -> Request<HelloRequest> {
+    Request::new(HelloRequest {
+        name: "Alice".into(),
+    })
+}
+
+fn hello_with_timeout(timeout: Duration) -> Request<HelloRequest> {
+    let mut request = hello();
+    request.set_timeout(timeout);
+    request
+}
+
+fn reply() -> HelloReply {
+    HelloReply {
+        message: "Hello".into(),
+    }
+}
+
+#[tokio::test]
+async fn records_the_deadline() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(reply),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    client
+        .say_hello(hello_with_timeout(Duration::from_millis(500)))
+        .await
+        .unwrap();
+    client.say_hello(hello()).await.unwrap();
+
+    let journal = server.journal();
+    assert_eq!(Some(Duration::from_millis(500)), journal[0].deadline);
+    assert_eq!(None, journal[1].deadline);
+}
+
+#[tokio::test]
+async fn delay_exceeding_the_deadline() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(reply)
+            .with_delay(Duration::from_secs(5)),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    // only the server knows about the deadline, the client does not time out on its own
+    let mut request = hello();
+    request
+        .metadata_mut()
+        .insert("grpc-timeout", "100m".parse().unwrap());
+
+    let started = Instant::now();
+    let status = client.say_hello(request).await.unwrap_err();
+    assert_eq!(Code::DeadlineExceeded, status.code());
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn delay_within_the_deadline() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(reply)
+            .with_delay(Duration::from_millis(50)),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    let response = client
+        .say_hello(hello_with_timeout(Duration::from_secs(5)))
+        .await
+        .unwrap();
+    assert_eq!(reply(), response.into_inner());
+}
+
+#[tokio::test]
+async fn deadline_at_most() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .deadline_at_most(Duration::from_secs(1))
+            .then()
+            .return_body(reply),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    client
+        .say_hello(hello_with_timeout(Duration::from_millis(500)))
+        .await
+        .unwrap();
+
+    let status = client
+        .say_hello(hello_with_timeout(Duration::from_secs(2)))
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+
+    let status = client.say_hello(hello()).await.unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
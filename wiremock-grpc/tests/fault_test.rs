@@ -0,0 +1,124 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use std::error::Error;
+
+use tonic::{transport::Channel, Code};
+use wiremock_grpc::*;
+
+fn hello(name: &str) -> HelloRequest {
+    HelloRequest { name: name.into() }
+}
+
+/// Fails the calls made by `Fault`, answers the ones made by `Alice`.
+async fn start(fault: Fault) -> (GreeterMockServer, GreeterClient<Channel>) {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let mut server = GreeterMockServer::start_with(GrpcServer::new(port).with_faults()).await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .body_eq(hello("Fault"))
+            .then()
+            .fault(fault),
+    );
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .body_eq(hello("Alice"))
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello Alice".into(),
+            }),
+    );
+
+    let client = GreeterClient::new(server.channel().await);
+    (server, client)
+}
+
+fn assert_go_away(status: &tonic::Status) {
+    let source = format!("{:?}", status.source());
+    assert!(source.contains("GoAway"), "{status}");
+}
+
+#[tokio::test]
+async fn reset_stream() {
+    let (_server, mut client) = start(Fault::ResetStream).await;
+
+    let status = client.say_hello(hello("Fault")).await.unwrap_err();
+    assert_eq!(Code::Internal, status.code(), "{status}");
+
+    // only the stream is reset
+    let response = client.say_hello(hello("Alice")).await.unwrap();
+    assert_eq!("Hello Alice", response.into_inner().message);
+}
+
+#[tokio::test]
+async fn close_connection() {
+    let (_server, mut client) = start(Fault::CloseConnection).await;
+
+    let status = client.say_hello(hello("Fault")).await.unwrap_err();
+    assert_eq!(Code::Unknown, status.code(), "{status}");
+
+    // the client reconnects
+    let response = client.say_hello(hello("Alice")).await.unwrap();
+    assert_eq!("Hello Alice", response.into_inner().message);
+}
+
+#[tokio::test]
+async fn go_away() {
+    let (_server, mut client) = start(Fault::GoAway).await;
+
+    let status = client.say_hello(hello("Fault")).await.unwrap_err();
+    assert_go_away(&status);
+
+    // the client reconnects
+    let response = client.say_hello(hello("Alice")).await.unwrap();
+    assert_eq!("Hello Alice", response.into_inner().message);
+}
+
+#[tokio::test]
+async fn empty_response() {
+    let (_server, mut client) = start(Fault::EmptyResponse).await;
+
+    let status = client.say_hello(hello("Fault")).await.unwrap_err();
+    assert_eq!(Code::Unknown, status.code(), "{status}");
+
+    let response = client.say_hello(hello("Alice")).await.unwrap();
+    assert_eq!("Hello Alice", response.into_inner().message);
+}
+
+#[tokio::test]
+async fn in_memory_go_away() {
+    let mut server = GreeterMockServer::start_with(GrpcServer::in_memory().with_faults()).await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .fault(Fault::GoAway),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    let status = client.say_hello(hello("Fault")).await.unwrap_err();
+    assert_go_away(&status);
+}
+
+#[tokio::test]
+#[should_panic(expected = "needs a server created with `with_faults()`")]
+async fn connection_faults_need_with_faults() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .fault(Fault::CloseConnection),
+    );
+}
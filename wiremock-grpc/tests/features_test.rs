@@ -11,7 +11,10 @@ mod wiremock_gen {
 use hello::{
     greeter_client::GreeterClient, HelloReply, HelloRequest, WeatherReply, WeatherRequest,
 };
-use std::net::TcpStream;
+use std::{
+    net::TcpStream,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use tonic::{transport::Channel, Code, Request};
 use wiremock_gen::*;
 use wiremock_grpc::*;
@@ -41,12 +44,7 @@ async fn default() {
 
     // Client
     // Client code is generated using tonic_build
-    let channel =
-        tonic::transport::Channel::from_shared(format!("http://[::1]:{}", server.address().port()))
-            .unwrap()
-            .connect()
-            .await
-            .unwrap();
+    let channel = server.channel().await;
     let mut client = GreeterClient::new(channel);
 
     // Act
@@ -286,15 +284,175 @@ async fn unmatched_request_panics() {
     assert_eq!(1, server.rules_unmatched());
 } // panics
 
+#[tokio::test]
+async fn delayed_response() {
+    let (mut server, mut client) = create().await;
+
+    server.setup(
+        MockBuilder::when()
+            .path("/hello.Greeter/SayHello")
+            .then()
+            .with_delay(std::time::Duration::from_millis(200))
+            .return_body(|| HelloReply {
+                message: "Hello".into(),
+            }),
+    );
+
+    let started = std::time::Instant::now();
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Mustakim".into(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!("Hello", response.into_inner().message);
+    assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn generic_server_accepts_any_service() {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let mut server = GrpcServer::new(port).start().await;
+
+    server.setup(
+        MockBuilder::when()
+            .path("/hello.Greeter/WeatherInfo")
+            .then()
+            .return_body(|| WeatherReply {
+                weather: "Sunny".into(),
+            }),
+    );
+
+    let mut client = GreeterClient::new(server.channel().await);
+    let response = client
+        .weather_info(WeatherRequest {
+            city: "Dhaka".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Sunny", response.into_inner().weather);
+
+    let status = client
+        .say_hello(HelloRequest {
+            name: "Mustakim".into(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
+
+#[tokio::test]
+async fn body_built_for_every_call() {
+    let (mut server, mut client) = create().await;
+
+    let calls = AtomicUsize::new(0);
+    server.setup(
+        MockBuilder::given("/hello.Greeter/SayHello").return_body_with(move || HelloReply {
+            message: format!("Hello #{}", calls.fetch_add(1, Ordering::Relaxed)),
+        }),
+    );
+
+    for expected in ["Hello #0", "Hello #1", "Hello #2"] {
+        let response = client
+            .say_hello(HelloRequest {
+                name: "Mustakim".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(expected, response.into_inner().message);
+    }
+}
+
+#[tokio::test]
+async fn identical_rules_found_apart() {
+    let (mut server, mut client) = create().await;
+
+    let reply = || HelloReply {
+        message: "Hello".into(),
+    };
+    let first = server.setup(
+        MockBuilder::when()
+            .path("/hello.Greeter/SayHello")
+            .header("session-id", "1")
+            .then()
+            .return_body(reply),
+    );
+    let second = server.setup(
+        MockBuilder::when()
+            .path("/hello.Greeter/SayHello")
+            .header("session-id", "2")
+            .then()
+            .return_body(reply),
+    );
+    assert_ne!(first, second);
+    assert_eq!(first, first.clone());
+
+    for session_id in ["1", "2", "2"] {
+        let mut request = Request::new(HelloRequest::default());
+        request
+            .metadata_mut()
+            .insert("session-id", session_id.parse().unwrap());
+        client.say_hello(request).await.unwrap();
+    }
+
+    assert_eq!(1, server.find(&first).unwrap().len());
+    assert_eq!(2, server.find(&second).unwrap().len());
+}
+
+#[tokio::test]
+async fn rules_built_from_one_base() {
+    let (mut server, mut client) = create().await;
+
+    let base = MockBuilder::when()
+        .path("/hello.Greeter/SayHello")
+        .then()
+        .return_body(|| HelloReply {
+            message: "Hello".into(),
+        });
+    let ok: MockBuilder = base.clone().return_status(Code::Ok).into();
+    server.setup(ok.clone());
+    let not_found = server.setup(base.return_status(Code::NotFound));
+    assert_ne!(ok, not_found);
+
+    client.say_hello(HelloRequest::default()).await.unwrap();
+
+    // found with the builder given to `setup`
+    assert_eq!(1, server.find(&ok).unwrap().len());
+    assert_eq!(0, server.find(&not_found).unwrap().len());
+    server.reset();
+}
+
 #[allow(dead_code)]
 async fn create() -> (MyMockServer, GreeterClient<Channel>) {
     let server = MyMockServer::start_default().await;
 
-    let channel =
-        tonic::transport::Channel::from_shared(format!("http://[::1]:{}", server.address().port()))
-            .unwrap()
-            .connect()
-            .await
-            .unwrap();
+    let channel = server.channel().await;
     (server, GreeterClient::new(channel))
 }
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn gzip_requests_with_default_features() {
+    let mut server = MyMockServer::start_default().await;
+    let rule = server.setup(
+        MockBuilder::when()
+            .path("/hello.Greeter/SayHello")
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello".into(),
+            }),
+    );
+
+    let response = GreeterClient::new(server.channel().await)
+        .send_compressed(tonic::codec::CompressionEncoding::Gzip)
+        .say_hello(HelloRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!("Hello", response.into_inner().message);
+    assert_eq!(
+        Some(tonic::codec::CompressionEncoding::Gzip),
+        server.find_one(&rule).encoding
+    );
+}
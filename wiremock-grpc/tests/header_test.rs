@@ -0,0 +1,203 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::{
+    metadata::{MetadataMap, MetadataValue},
+    Code, Request,
+};
+use wiremock_grpc::*;
+
+/// Whether a request with `metadata` matches the rule built by `when`.
+async fn matches(
+    when: impl FnOnce(WhenBuilder) -> WhenBuilder,
+    metadata: impl FnOnce(&mut MetadataMap),
+) -> bool {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        when(MockBuilder::when().path_say_hello())
+            .then()
+            .return_body(HelloReply::default),
+    );
+
+    let mut request = Request::new(HelloRequest::default());
+    metadata(request.metadata_mut());
+    let result = GreeterClient::new(server.channel().await)
+        .say_hello(request)
+        .await;
+
+    // the rule must be matched for the server to be dropped
+    server.reset();
+    match result {
+        Ok(_) => true,
+        Err(status) if status.code() == Code::Unimplemented => false,
+        Err(status) => panic!("unexpected status {status}"),
+    }
+}
+
+#[tokio::test]
+async fn header_exists() {
+    let when = |w: WhenBuilder| w.header_exists("x-session-id");
+
+    assert!(
+        matches(when, |m| {
+            m.insert("x-session-id", "abc".parse().unwrap());
+        })
+        .await
+    );
+    assert!(!matches(when, |_| {}).await);
+}
+
+#[tokio::test]
+async fn header_missing() {
+    let when = |w: WhenBuilder| w.header_missing("authorization");
+
+    assert!(matches(when, |_| {}).await);
+    assert!(
+        !matches(when, |m| {
+            m.insert("authorization", "Bearer token".parse().unwrap());
+        })
+        .await
+    );
+}
+
+#[tokio::test]
+async fn header_prefix() {
+    let when = |w: WhenBuilder| w.header_prefix("authorization", "Bearer ");
+
+    assert!(
+        matches(when, |m| {
+            m.insert("authorization", "Bearer token".parse().unwrap());
+        })
+        .await
+    );
+    assert!(
+        !matches(when, |m| {
+            m.insert("authorization", "Basic dXNlcg==".parse().unwrap());
+        })
+        .await
+    );
+}
+
+#[tokio::test]
+async fn header_eq_ignore_case() {
+    let when = |w: WhenBuilder| w.header_eq_ignore_case("x-env", "Staging");
+
+    assert!(
+        matches(when, |m| {
+            m.insert("x-env", "STAGING".parse().unwrap());
+        })
+        .await
+    );
+    assert!(
+        !matches(when, |m| {
+            m.insert("x-env", "production".parse().unwrap());
+        })
+        .await
+    );
+}
+
+#[tokio::test]
+async fn header_regex() {
+    let when = |w: WhenBuilder| w.header_regex("x-request-id", "^[0-9a-f]{8}$");
+
+    assert!(
+        matches(when, |m| {
+            m.insert("x-request-id", "deadbeef".parse().unwrap());
+        })
+        .await
+    );
+    assert!(
+        !matches(when, |m| {
+            m.insert("x-request-id", "not-an-id".parse().unwrap());
+        })
+        .await
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "Invalid regular expression")]
+async fn header_invalid_regex() {
+    let _ = MockBuilder::when()
+        .path_say_hello()
+        .header_regex("x-id", "(");
+}
+
+#[tokio::test]
+async fn header_values() {
+    let when = |w: WhenBuilder| w.header_values("x-role", ["admin", "reader"]);
+
+    assert!(
+        matches(when, |m| {
+            m.append("x-role", "reader".parse().unwrap());
+            m.append("x-role", "writer".parse().unwrap());
+            m.append("x-role", "admin".parse().unwrap());
+        })
+        .await
+    );
+    assert!(
+        !matches(when, |m| {
+            m.append("x-role", "reader".parse().unwrap());
+        })
+        .await
+    );
+}
+
+#[tokio::test]
+async fn header_bin() {
+    let when = |w: WhenBuilder| w.header_bin("x-trace-bin", [0u8, 1, 2, 255]);
+
+    assert!(
+        matches(when, |m| {
+            m.insert_bin("x-trace-bin", MetadataValue::from_bytes(&[0, 1, 2, 255]));
+        })
+        .await
+    );
+    assert!(
+        !matches(when, |m| {
+            m.insert_bin("x-trace-bin", MetadataValue::from_bytes(&[0, 1, 2]));
+        })
+        .await
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "must end with `-bin`")]
+async fn header_bin_requires_bin_suffix() {
+    let _ = MockBuilder::when()
+        .path_say_hello()
+        .header_bin("x-trace", [0u8]);
+}
+
+#[tokio::test]
+async fn combined_with_exact_headers() {
+    let when = |w: WhenBuilder| {
+        w.header("x-tenant", "acme")
+            .header_exists("x-session-id")
+            .header_missing("x-debug")
+    };
+
+    assert!(
+        matches(when, |m| {
+            m.insert("x-tenant", "acme".parse().unwrap());
+            m.insert("x-session-id", "abc".parse().unwrap());
+        })
+        .await
+    );
+    assert!(
+        !matches(when, |m| {
+            m.insert("x-tenant", "acme".parse().unwrap());
+            m.insert("x-session-id", "abc".parse().unwrap());
+            m.insert("x-debug", "1".parse().unwrap());
+        })
+        .await
+    );
+}
@@ -0,0 +1,113 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use tonic::{transport::Channel, Code};
+use tonic_health::pb::{
+    health_check_response::ServingStatus as Status, health_client::HealthClient, HealthCheckRequest,
+};
+use wiremock_grpc::*;
+
+async fn check(channel: Channel, service: &str) -> Result<Status, tonic::Status> {
+    let response = HealthClient::new(channel)
+        .check(HealthCheckRequest {
+            service: service.into(),
+        })
+        .await?;
+    Ok(response.into_inner().status())
+}
+
+async fn start() -> GreeterMockServer {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    GreeterMockServer::start_with(GrpcServer::new(port).with_health()).await
+}
+
+#[tokio::test]
+async fn reports_the_mocked_services_serving() {
+    let server = start().await;
+
+    assert_eq!(
+        Status::Serving,
+        check(server.channel().await, "").await.unwrap()
+    );
+    assert_eq!(
+        Status::Serving,
+        check(server.channel().await, "hello.Greeter")
+            .await
+            .unwrap()
+    );
+
+    let status = check(server.channel().await, "hello.Unknown")
+        .await
+        .unwrap_err();
+    assert_eq!(Code::NotFound, status.code());
+}
+
+#[tokio::test]
+async fn set_health() {
+    let server = start().await;
+
+    server
+        .set_health("hello.Greeter", ServingStatus::NotServing)
+        .await;
+    assert_eq!(
+        Status::NotServing,
+        check(server.channel().await, "hello.Greeter")
+            .await
+            .unwrap()
+    );
+    // the other services are not affected
+    assert_eq!(
+        Status::Serving,
+        check(server.channel().await, "").await.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn watch_streams_the_changes() {
+    let server = start().await;
+
+    let mut updates = HealthClient::new(server.channel().await)
+        .watch(HealthCheckRequest {
+            service: "hello.Greeter".into(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let mut next = async || updates.message().await.unwrap().unwrap().status();
+
+    assert_eq!(Status::Serving, next().await);
+
+    server
+        .set_health("hello.Greeter", ServingStatus::NotServing)
+        .await;
+    assert_eq!(Status::NotServing, next().await);
+
+    server
+        .set_health("hello.Greeter", ServingStatus::Serving)
+        .await;
+    assert_eq!(Status::Serving, next().await);
+}
+
+#[tokio::test]
+async fn disabled_by_default() {
+    let server = GreeterMockServer::start_default().await;
+
+    let status = check(server.channel().await, "").await.unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
+
+#[tokio::test]
+#[should_panic(expected = "The health service is not served")]
+async fn set_health_without_the_service_panics() {
+    let server = GreeterMockServer::start_default().await;
+
+    server.set_health("", ServingStatus::NotServing).await;
+}
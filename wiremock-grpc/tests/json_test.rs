@@ -0,0 +1,131 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+        WeatherInfo,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloRequest, WeatherRequest};
+use tonic::Code;
+use wiremock_grpc::*;
+
+const DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/hello.bin"));
+
+#[tokio::test]
+async fn json_bodies() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.add_descriptors(DESCRIPTOR_SET).unwrap();
+
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .body_json(r#"{"name": "Alice"}"#)
+            .then()
+            .return_json(r#"{"message": "Hello Alice"}"#),
+    );
+    server.setup(
+        MockBuilder::when()
+            .path_weather_info()
+            .body_json_contains(r#"{"city": "Dhaka"}"#)
+            .then()
+            .return_status(Code::Ok)
+            .return_json(r#"{"weather": "Sunny"}"#),
+    );
+
+    let mut client = GreeterClient::new(server.channel().await);
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Hello Alice", response.into_inner().message);
+
+    let status = client
+        .say_hello(HelloRequest { name: "Bob".into() })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+
+    let response = client
+        .weather_info(WeatherRequest {
+            city: "Dhaka".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Sunny", response.into_inner().weather);
+}
+
+#[tokio::test]
+async fn json_rule_equals_the_registered_rule() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.add_descriptors(DESCRIPTOR_SET).unwrap();
+
+    let rule = server.setup(
+        MockBuilder::given("/hello.Greeter/SayHello").return_json(r#"{"message": "Hello"}"#),
+    );
+
+    let mut client = GreeterClient::new(server.channel().await);
+    client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap();
+
+    let request: HelloRequest = server.find_one(&rule).decode_body().unwrap();
+    assert_eq!("Alice", request.name);
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unable to encode the JSON messages of /hello.Greeter/SayHello")]
+async fn json_without_descriptors_panics() {
+    let mut server = GreeterMockServer::start_default().await;
+
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_json(r#"{"message": "Hello"}"#),
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "unrecognized field name")]
+async fn json_of_the_wrong_message_panics() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.add_descriptors(DESCRIPTOR_SET).unwrap();
+
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_json(r#"{"weather": "Sunny"}"#),
+    );
+}
+
+#[tokio::test]
+async fn mounted_json_rule() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.add_descriptors(DESCRIPTOR_SET).unwrap();
+
+    MockBuilder::when()
+        .path_say_hello()
+        .body_json(r#"{"name": "Alice"}"#)
+        .then()
+        .return_json(r#"{"message": "Hello Alice"}"#)
+        .mount(&mut server);
+
+    let response = GreeterClient::new(server.channel().await)
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Hello Alice", response.into_inner().message);
+}
@@ -0,0 +1,108 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::{Code, Request};
+use wiremock_grpc::*;
+
+/// Matches the requests carrying a token with the `admin` role.
+struct AdminToken;
+
+impl Match for AdminToken {
+    fn matches(&self, request: &GrpcRequest<'_>) -> bool {
+        request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token.split('.').any(|claim| claim == "role=admin"))
+    }
+}
+
+fn hello(name: &str, token: Option<&str>) -> Request<HelloRequest> {
+    let mut request = Request::new(HelloRequest { name: name.into() });
+    if let Some(token) = token {
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {token}").parse().unwrap());
+    }
+    request
+}
+
+#[tokio::test]
+async fn custom_matcher() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .matching(AdminToken)
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello admin".into(),
+            }),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    let response = client
+        .say_hello(hello("Alice", Some("user=alice.role=admin")))
+        .await
+        .unwrap();
+    assert_eq!("Hello admin", response.into_inner().message);
+
+    let status = client
+        .say_hello(hello("Bob", Some("user=bob.role=reader")))
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+
+    let status = client.say_hello(hello("Eve", None)).await.unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
+
+#[tokio::test]
+async fn closures_on_the_decoded_body() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .matching(|request: &GrpcRequest| request.path() == "/hello.Greeter/SayHello")
+            .matching(|request: &GrpcRequest| {
+                request
+                    .decode_body::<HelloRequest>()
+                    .is_ok_and(|body| body.name.len() <= 5)
+            })
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello".into(),
+            }),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    client.say_hello(hello("Alice", None)).await.unwrap();
+
+    let status = client
+        .say_hello(hello("Bartholomew", None))
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
+
+#[test]
+fn rules_with_matchers_stay_clone_and_debug() {
+    let rule: MockBuilder = MockBuilder::when()
+        .path_say_hello()
+        .matching(AdminToken)
+        .then()
+        .return_status(Code::Ok)
+        .into();
+
+    assert!(format!("{:?}", rule.clone()).contains("Matcher(..)"));
+}
@@ -0,0 +1,169 @@
+mod hello {
+    tonic::include_proto!("hello");
+
+    pub mod extended {
+        tonic::include_proto!("hello.extended");
+    }
+}
+
+mod multi {
+    tonic::include_proto!("_");
+    wiremock_grpc::include_mock!("_");
+}
+
+mod macro_test {
+    use super::hello::{
+        extended::{self, extended_greeter_client::ExtendedGreeterClient},
+        greeter_client::GreeterClient,
+        HelloReply, HelloRequest,
+    };
+    use super::multi::{ping_client::PingClient, EchoMessage};
+    use wiremock_grpc::*;
+
+    wiremock_grpc::generate_svc! {
+        package hello;
+        service Greeter as CombinedServer {
+            SayHello,
+        }
+
+        package hello.extended;
+        service ExtendedGreeter {
+            SayHello,
+        }
+
+        package;
+        service Ping {
+            /// Replies to a ping
+            #[allow(clippy::needless_pass_by_ref_mut)]
+            Ping(EchoMessage) -> EchoMessage,
+        }
+    }
+
+    #[tokio::test]
+    async fn one_server_for_every_service() {
+        let mut server = CombinedServer::start_default().await;
+
+        // both services have a `SayHello` rpc
+        server.setup(
+            GreeterTypeSafeExt::path_say_hello(&MockBuilder::when())
+                .then()
+                .return_body(|| HelloReply {
+                    message: "Hello".into(),
+                }),
+        );
+        server.setup(
+            ExtendedGreeterTypeSafeExt::path_say_hello(&MockBuilder::when())
+                .then()
+                .return_body(|| HelloReply {
+                    message: "Extended hello".into(),
+                }),
+        );
+        let ping =
+            server.setup(
+                MockBuilder::when()
+                    .path_ping()
+                    .then()
+                    .return_body(|| EchoMessage {
+                        text: "Pong".into(),
+                    }),
+            );
+
+        let channel = server.channel().await;
+
+        let response = GreeterClient::new(channel.clone())
+            .say_hello(HelloRequest {
+                name: "Test".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!("Hello", response.into_inner().message);
+
+        let response = ExtendedGreeterClient::new(channel.clone())
+            .say_hello(extended::HelloRequest {
+                name: "Test".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!("Extended hello", response.into_inner().message);
+
+        let response = PingClient::new(channel)
+            .ping(EchoMessage {
+                text: "Ping".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!("Pong", response.into_inner().text);
+
+        assert_eq!(3, server.find_request_count());
+        let ping = server.find_one(&ping);
+        assert_eq!("Ping", ping.decode_body::<EchoMessage>().unwrap().text);
+    }
+}
+
+mod proto_file_test {
+    use super::multi::{echo_client::EchoClient, ping_client::PingClient, EchoMessage};
+    use wiremock_grpc::*;
+
+    // every service of the file
+    wiremock_grpc::generate_svc!(proto = "proto/multi.proto", server = MultiServer);
+
+    #[tokio::test]
+    async fn every_service_of_a_proto_without_package() {
+        let mut server = MultiServer::start_in_memory().await;
+
+        server.setup(
+            MockBuilder::when()
+                .path_echo()
+                .then()
+                .return_body(|| EchoMessage {
+                    text: "Echo".into(),
+                }),
+        );
+        server.setup(
+            MockBuilder::when()
+                .path_pong()
+                .then()
+                .return_body(|| EchoMessage {
+                    text: "Pong".into(),
+                }),
+        );
+
+        let channel = server.channel().await;
+        let response = EchoClient::new(channel.clone())
+            .echo(EchoMessage::default())
+            .await
+            .unwrap();
+        assert_eq!("Echo", response.into_inner().text);
+
+        let response = PingClient::new(channel)
+            .pong(EchoMessage::default())
+            .await
+            .unwrap();
+        assert_eq!("Pong", response.into_inner().text);
+    }
+}
+
+mod build_test {
+    use super::multi::{ping_client::PingClient, EchoMessage, PingMockServer};
+    use wiremock_grpc::MockBuilder;
+
+    #[tokio::test]
+    async fn generated_mock_server_without_package() {
+        use super::multi::PingTypeSafeExt;
+
+        let mut server = PingMockServer::start_default().await;
+        server.setup(
+            MockBuilder::when()
+                .path_ping()
+                .then()
+                .return_body(|| EchoMessage {
+                    text: "Pong".into(),
+                }),
+        );
+
+        let mut client = server.client(PingClient::new).await;
+        let response = client.ping(EchoMessage::default()).await.unwrap();
+
+        assert_eq!("Pong", response.into_inner().text);
+    }
+}
@@ -0,0 +1,141 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+        WeatherInfo,
+    }
+}
+
+use hello::{
+    greeter_client::GreeterClient, HelloReply, HelloRequest, WeatherReply, WeatherRequest,
+};
+use std::panic::AssertUnwindSafe;
+
+use tonic::transport::Channel;
+use wiremock_grpc::*;
+
+struct Session {
+    server: GreeterMockServer,
+    client: GreeterClient<Channel>,
+    login: MockBuilder,
+    fetch: MockBuilder,
+    logout: MockBuilder,
+}
+
+impl Session {
+    async fn start() -> Self {
+        let mut server = GreeterMockServer::start_default().await;
+        let mut hello = |name: &'static str| {
+            server.setup(
+                MockBuilder::when()
+                    .path_say_hello()
+                    .body_eq(HelloRequest { name: name.into() })
+                    .then()
+                    .return_body(move || HelloReply {
+                        message: name.into(),
+                    }),
+            )
+        };
+        let login = hello("login");
+        let logout = hello("logout");
+        let fetch = server.setup(
+            MockBuilder::when()
+                .path_weather_info()
+                .then()
+                .return_body(WeatherReply::default),
+        );
+        let client = GreeterClient::new(server.channel().await);
+
+        Self {
+            server,
+            client,
+            login,
+            fetch,
+            logout,
+        }
+    }
+
+    async fn hello(&mut self, name: &str) {
+        self.client
+            .say_hello(HelloRequest { name: name.into() })
+            .await
+            .unwrap();
+    }
+
+    async fn fetch(&mut self) {
+        self.client
+            .weather_info(WeatherRequest::default())
+            .await
+            .unwrap();
+    }
+
+    fn assert_called_in_order(&self) {
+        self.server
+            .assert_called_in_order(&[&self.login, &self.fetch, &self.logout]);
+    }
+}
+
+#[tokio::test]
+async fn called_in_order() {
+    let mut session = Session::start().await;
+    session.fetch().await;
+    session.hello("login").await;
+    session.fetch().await;
+    session.fetch().await;
+    session.hello("logout").await;
+    session.hello("login").await;
+
+    session.assert_called_in_order();
+
+    let journal = session.server.journal();
+    assert!(journal.windows(2).all(|w| w[0].sequence < w[1].sequence));
+}
+
+#[tokio::test]
+#[should_panic(expected = "The rules were not called in order")]
+async fn called_out_of_order() {
+    let mut session = Session::start().await;
+    session.hello("login").await;
+    session.hello("logout").await;
+    session.fetch().await;
+
+    session.assert_called_in_order();
+}
+
+#[tokio::test]
+async fn prints_the_timeline() {
+    let mut session = Session::start().await;
+    session.hello("login").await;
+    session.hello("logout").await;
+    session.fetch().await;
+
+    let message = std::panic::catch_unwind(AssertUnwindSafe(|| session.assert_called_in_order()))
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+
+    let journal = session.server.journal();
+    let timeline = [(0, 1), (1, 3), (2, 2)].map(|(i, rule)| {
+        format!(
+            "  #{} {} (rule {rule})",
+            journal[i].sequence, journal[i].uri
+        )
+    });
+    assert!(message.contains(&timeline.join("\n")), "{message}");
+}
+
+#[tokio::test]
+async fn never_called() {
+    let mut session = Session::start().await;
+    session.hello("login").await;
+    session.hello("logout").await;
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| session.assert_called_in_order()));
+    // the rule must be matched for the server to be dropped
+    session.server.reset();
+    assert!(result.is_err());
+}
@@ -0,0 +1,146 @@
+mod hello {
+    tonic::include_proto!("hello");
+
+    pub mod extended {
+        tonic::include_proto!("hello.extended");
+    }
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+        WeatherInfo,
+    }
+
+    package hello.extended;
+    service ExtendedGreeter {
+        SayHello,
+    }
+}
+
+use hello::{
+    extended::extended_greeter_client::ExtendedGreeterClient, greeter_client::GreeterClient,
+    HelloReply, HelloRequest, WeatherRequest,
+};
+use tonic::Code;
+use wiremock_grpc::*;
+
+/// The codes of a `SayHello` and a `WeatherInfo` to `Greeter`, and a `SayHello` to `ExtendedGreeter`.
+async fn codes(when: WhenBuilder) -> [Code; 3] {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(when.then().return_status(Code::Unavailable));
+
+    let channel = server.channel().await;
+    let mut client = GreeterClient::new(channel.clone());
+    let codes = [
+        client
+            .say_hello(HelloRequest::default())
+            .await
+            .unwrap_err()
+            .code(),
+        client
+            .weather_info(WeatherRequest::default())
+            .await
+            .unwrap_err()
+            .code(),
+        ExtendedGreeterClient::new(channel)
+            .say_hello(hello::extended::HelloRequest::default())
+            .await
+            .unwrap_err()
+            .code(),
+    ];
+
+    // the rule may not be matched at all
+    server.reset();
+    codes
+}
+
+#[tokio::test]
+async fn any_method_of() {
+    assert_eq!(
+        [Code::Unavailable, Code::Unavailable, Code::Unimplemented],
+        codes(MockBuilder::when().any_method_of::<GreeterMockServer>()).await
+    );
+}
+
+#[tokio::test]
+async fn any_service_method() {
+    assert_eq!(
+        [Code::Unavailable, Code::Unavailable, Code::Unimplemented],
+        codes(MockBuilder::when().any_greeter_method()).await
+    );
+    assert_eq!(
+        [Code::Unimplemented, Code::Unimplemented, Code::Unavailable],
+        codes(MockBuilder::when().any_extended_greeter_method()).await
+    );
+}
+
+#[tokio::test]
+async fn path_prefix() {
+    assert_eq!(
+        [Code::Unavailable, Code::Unavailable, Code::Unavailable],
+        codes(MockBuilder::when().path_prefix("/hello.")).await
+    );
+}
+
+#[tokio::test]
+async fn path_glob() {
+    assert_eq!(
+        [Code::Unavailable, Code::Unimplemented, Code::Unavailable],
+        codes(MockBuilder::when().path_glob("/hello.*/Say*")).await
+    );
+    // `*` stops at the separators, `**` does not
+    assert_eq!(
+        [
+            Code::Unimplemented,
+            Code::Unimplemented,
+            Code::Unimplemented
+        ],
+        codes(MockBuilder::when().path_glob("/*")).await
+    );
+    assert_eq!(
+        [Code::Unavailable, Code::Unavailable, Code::Unavailable],
+        codes(MockBuilder::when().path_glob("/**")).await
+    );
+}
+
+#[tokio::test]
+async fn path_regex() {
+    assert_eq!(
+        [Code::Unimplemented, Code::Unavailable, Code::Unimplemented],
+        codes(MockBuilder::when().path_regex("^/hello\\.Greeter/Weather")).await
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "Invalid regular expression")]
+async fn path_invalid_regex() {
+    let _ = MockBuilder::when().path_regex("(");
+}
+
+#[tokio::test]
+async fn exact_rules_take_precedence_when_set_up_first() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        GreeterTypeSafeExt::path_say_hello(&MockBuilder::when())
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello".into(),
+            }),
+    );
+    server.setup(
+        MockBuilder::when()
+            .any_greeter_method()
+            .then()
+            .return_status(Code::Unavailable),
+    );
+    let mut client = GreeterClient::new(server.channel().await);
+
+    client.say_hello(HelloRequest::default()).await.unwrap();
+    let status = client
+        .weather_info(WeatherRequest::default())
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unavailable, status.code());
+}
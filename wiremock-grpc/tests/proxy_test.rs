@@ -0,0 +1,202 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+mod streaming {
+    tonic::include_proto!("streaming");
+    wiremock_grpc::include_mock!("streaming");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello(HelloRequest) -> HelloReply,
+        WeatherInfo(WeatherRequest) -> WeatherReply,
+    }
+}
+
+use std::pin::Pin;
+
+use hello::{
+    greeter_client::GreeterClient, HelloReply, HelloRequest, WeatherReply, WeatherRequest,
+};
+use streaming::{
+    chat_client::ChatClient,
+    chat_server::{Chat, ChatServer},
+    ChatMessage, ChatMockServer,
+};
+use tonic::{
+    codegen::tokio_stream::{self, Stream, StreamExt},
+    transport::{Endpoint, Server},
+    Code, Request, Response, Status, Streaming,
+};
+use wiremock_grpc::*;
+
+fn endpoint(address: &std::net::SocketAddr) -> Endpoint {
+    Endpoint::from_shared(format!("http://{address}")).unwrap()
+}
+
+#[tokio::test]
+async fn unmatched_requests_are_proxied() {
+    let mut upstream = GreeterMockServer::start_default().await;
+    upstream.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello from upstream".into(),
+            })
+            .return_header("x-server", "upstream"),
+    );
+
+    let mut server = GreeterMockServer::start_default().await;
+    server.proxy_unmatched_to(endpoint(upstream.address()));
+    server.setup(
+        MockBuilder::when()
+            .path_weather_info()
+            .then()
+            .return_body(|| WeatherReply {
+                weather: "Mocked".into(),
+            }),
+    );
+
+    let mut client = GreeterClient::new(server.channel().await);
+
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Proxy".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("upstream", response.metadata().get("x-server").unwrap());
+    assert_eq!("Hello from upstream", response.into_inner().message);
+
+    let response = client
+        .weather_info(WeatherRequest {
+            city: "London".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("Mocked", response.into_inner().weather);
+
+    let journal = server.journal();
+    assert_eq!(2, journal.len());
+    assert!(journal[0].proxied);
+    assert_eq!(
+        "Proxy",
+        journal[0].decode_body::<HelloRequest>().unwrap().name
+    );
+    assert!(!journal[1].proxied);
+
+    // only the upstream saw the proxied request
+    assert_eq!(1, server.find_request_count());
+    assert_eq!(1, upstream.find_request_count());
+}
+
+#[tokio::test]
+async fn unreachable_upstream() {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let server = GreeterMockServer::start_default().await;
+    server.proxy_unmatched_to(endpoint(&format!("[::1]:{port}").parse().unwrap()));
+
+    let status = GreeterClient::new(server.channel().await)
+        .say_hello(HelloRequest::default())
+        .await
+        .unwrap_err();
+
+    assert_eq!(Code::Unavailable, status.code());
+    assert!(server.journal()[0].proxied);
+}
+
+#[tokio::test]
+async fn journal_keeps_the_last_requests() {
+    let mut server = GreeterMockServer::start_default().await;
+    let hello = server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(HelloReply::default),
+    );
+    server.setup(
+        MockBuilder::when()
+            .path_weather_info()
+            .then()
+            .return_body(WeatherReply::default),
+    );
+    server.journal_capacity(2);
+
+    let mut client = GreeterClient::new(server.channel().await);
+    for name in ["first", "second"] {
+        client
+            .say_hello(HelloRequest { name: name.into() })
+            .await
+            .unwrap();
+    }
+    client
+        .weather_info(WeatherRequest::default())
+        .await
+        .unwrap();
+
+    let journal = server.journal();
+    assert_eq!(2, journal.len());
+    assert_eq!(
+        "second",
+        journal[0].decode_body::<HelloRequest>().unwrap().name
+    );
+    assert!(journal[1].uri.ends_with("/WeatherInfo"));
+    // the rules still see every request
+    assert_eq!(2, server.find(&hello).unwrap().len());
+
+    server.journal_capacity(0);
+    assert!(server.journal().is_empty());
+}
+
+/// A real server replying to each message with its uppercase version.
+struct Upper;
+
+#[tonic::async_trait]
+impl Chat for Upper {
+    type EchoStream = Pin<Box<dyn Stream<Item = Result<ChatMessage, Status>> + Send>>;
+
+    async fn echo(
+        &self,
+        request: Request<Streaming<ChatMessage>>,
+    ) -> Result<Response<Self::EchoStream>, Status> {
+        let stream = request.into_inner().map(|message| {
+            message.map(|m| ChatMessage {
+                text: m.text.to_uppercase(),
+            })
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[tokio::test]
+async fn streaming_calls_are_proxied() {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let address: std::net::SocketAddr = format!("[::1]:{port}").parse().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(ChatServer::new(Upper))
+            .serve(address),
+    );
+
+    let server = ChatMockServer::start_in_memory().await;
+    server.proxy_unmatched_to(endpoint(&address));
+
+    let mut client = server.client(ChatClient::new).await;
+    let messages = ["a", "b", "c"].map(|text| ChatMessage { text: text.into() });
+    let response = client.echo(tokio_stream::iter(messages)).await.unwrap();
+
+    let replies: Vec<String> = response
+        .into_inner()
+        .map(|m| m.unwrap().text)
+        .collect()
+        .await;
+    assert_eq!(vec!["A", "B", "C"], replies);
+
+    let journal = server.journal();
+    assert_eq!(1, journal.len());
+    assert!(journal[0].proxied);
+    assert_eq!("a", journal[0].decode_body::<ChatMessage>().unwrap().text);
+}
@@ -0,0 +1,121 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use std::time::Duration;
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::Code;
+use wiremock_grpc::*;
+
+fn reply() -> HelloReply {
+    HelloReply {
+        message: "Hello from a raw frame".into(),
+    }
+}
+
+/// The status the client fails with when receiving `frame`.
+async fn call(frame: Vec<u8>) -> tonic::Status {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_raw_frame(frame),
+    );
+
+    let mut client = GreeterClient::new(server.channel().await);
+    let call = client.say_hello(HelloRequest {
+        name: "Alice".into(),
+    });
+    tokio::time::timeout(Duration::from_secs(5), call)
+        .await
+        .expect("The client hangs")
+        .unwrap_err()
+}
+
+#[tokio::test]
+async fn well_formed_frame() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_raw_frame(raw_frame::frame(
+                false,
+                &prost::Message::encode_to_vec(&reply()),
+            ))
+            .return_header("x-raw", "yes"),
+    );
+
+    let mut client = GreeterClient::new(server.channel().await);
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("yes", response.metadata().get("x-raw").unwrap());
+    assert_eq!(reply(), response.into_inner());
+}
+
+#[tokio::test]
+async fn raw_frame_with_status() {
+    let mut server = GreeterMockServer::start_default().await;
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_raw_frame(Vec::new())
+            .return_status(Code::NotFound),
+    );
+
+    let mut client = GreeterClient::new(server.channel().await);
+    let status = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::NotFound, status.code());
+}
+
+#[tokio::test]
+async fn truncated() {
+    let status = call(raw_frame::truncated(reply())).await;
+    assert_eq!(Code::Internal, status.code(), "{status}");
+}
+
+#[tokio::test]
+async fn wrong_length_prefix() {
+    let status = call(raw_frame::wrong_length_prefix(reply())).await;
+    assert_eq!(Code::Internal, status.code(), "{status}");
+}
+
+#[tokio::test]
+async fn compressed_flag_without_compression() {
+    let status = call(raw_frame::compressed_flag_without_compression(reply())).await;
+    assert_eq!(Code::Internal, status.code(), "{status}");
+}
+
+#[tokio::test]
+async fn invalid_wire_type() {
+    let status = call(raw_frame::invalid_wire_type()).await;
+    assert_eq!(Code::Internal, status.code(), "{status}");
+}
+
+#[tokio::test]
+async fn oversized() {
+    let status = call(raw_frame::oversized(
+        raw_frame::DEFAULT_MAX_DECODING_SIZE + 1,
+    ))
+    .await;
+    assert_eq!(Code::OutOfRange, status.code(), "{status}");
+}
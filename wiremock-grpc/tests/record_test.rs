@@ -0,0 +1,102 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello(HelloRequest) -> HelloReply,
+        WeatherInfo(WeatherRequest) -> WeatherReply,
+    }
+}
+
+use hello::{
+    greeter_client::GreeterClient, HelloReply, HelloRequest, WeatherReply, WeatherRequest,
+};
+use tonic::Code;
+use wiremock_grpc::*;
+
+async fn call(server: &GreeterMockServer) {
+    let mut client = GreeterClient::new(server.channel().await);
+
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("upstream", response.metadata().get("x-server").unwrap());
+    assert_eq!("1", response.metadata().get("x-trailer").unwrap());
+    assert_eq!("Hello Alice", response.into_inner().message);
+
+    let status = client
+        .weather_info(WeatherRequest {
+            city: "Atlantis".into(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::NotFound, status.code());
+}
+
+#[tokio::test]
+async fn record_then_replay() {
+    let path = std::env::temp_dir().join(format!(
+        "wiremock-grpc-recording-{}.json",
+        std::process::id()
+    ));
+
+    {
+        let mut upstream = GreeterMockServer::start_default().await;
+        upstream.setup(
+            MockBuilder::when()
+                .path_say_hello()
+                .body_eq(HelloRequest {
+                    name: "Alice".into(),
+                })
+                .then()
+                .return_body(|| HelloReply {
+                    message: "Hello Alice".into(),
+                })
+                .return_header("x-server", "upstream")
+                .return_trailer("x-trailer", "1"),
+        );
+        upstream.setup(
+            MockBuilder::when()
+                .path_weather_info()
+                .then()
+                .return_status(Code::NotFound),
+        );
+
+        let server = GreeterMockServer::start_default().await;
+        server.record_to(upstream.channel().await, &path);
+        call(&server).await;
+
+        // forwarded requests do not match any rule of the recording server
+        assert_eq!(0, server.find_request_count());
+        assert_eq!(2, upstream.find_request_count());
+    }
+
+    let mut server = GreeterMockServer::start_in_memory().await;
+    let rules = server.replay_from(&path).unwrap();
+    assert_eq!(2, rules.len());
+
+    call(&server).await;
+    assert_eq!(
+        "Alice",
+        server
+            .find_one(&rules[0])
+            .decode_body::<HelloRequest>()
+            .unwrap()
+            .name
+    );
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn replay_missing_file() {
+    let mut server = GreeterMockServer::start_in_memory().await;
+    let err = server.replay_from("does/not/exist.json").unwrap_err();
+
+    assert_eq!(std::io::ErrorKind::NotFound, err.kind());
+}
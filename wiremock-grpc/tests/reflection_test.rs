@@ -0,0 +1,118 @@
+mod multi {
+    tonic::include_proto!("_");
+    wiremock_grpc::include_mock!("_");
+}
+
+use multi::{echo_client::EchoClient, EchoMessage, EchoMockServer, EchoTypeSafeExt};
+use tonic::{transport::Channel, Code};
+use tonic_reflection::pb::v1::{
+    server_reflection_client::ServerReflectionClient, server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse, ServerReflectionRequest,
+};
+use wiremock_grpc::*;
+
+const HELLO: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/hello.bin"));
+const MULTI: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/multi.bin"));
+
+async fn reflect(
+    channel: Channel,
+    request: MessageRequest,
+) -> Result<MessageResponse, tonic::Status> {
+    let mut client = ServerReflectionClient::new(channel);
+    let request = ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(request),
+    };
+
+    let mut responses = client
+        .server_reflection_info(tonic::codegen::tokio_stream::iter([request]))
+        .await?
+        .into_inner();
+    let response = responses.message().await?.unwrap();
+    Ok(response.message_response.unwrap())
+}
+
+async fn services(channel: Channel) -> Vec<String> {
+    match reflect(channel, MessageRequest::ListServices(String::new()))
+        .await
+        .unwrap()
+    {
+        MessageResponse::ListServicesResponse(list) => {
+            list.service.into_iter().map(|s| s.name).collect()
+        }
+        response => panic!("Unexpected response {response:?}"),
+    }
+}
+
+#[tokio::test]
+async fn advertises_the_mocked_services() {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let mut server = EchoMockServer::start_with(GrpcServer::new(port).with_reflection(MULTI)).await;
+
+    let mut services = services(server.channel().await).await;
+    services.sort();
+    // `Ping` is in the descriptor set, but not mocked
+    assert_eq!(
+        vec!["Echo", "grpc.reflection.v1.ServerReflection"],
+        services
+    );
+
+    let response = reflect(
+        server.channel().await,
+        MessageRequest::FileContainingSymbol("Echo".into()),
+    )
+    .await
+    .unwrap();
+    assert!(
+        matches!(response, MessageResponse::FileDescriptorResponse(ref files) if !files.file_descriptor_proto.is_empty()),
+        "{response:?}"
+    );
+
+    // the mock is still served on the same listener
+    server.setup(
+        MockBuilder::when()
+            .path_echo()
+            .then()
+            .return_body(|| EchoMessage { text: "hi".into() }),
+    );
+    let response = EchoClient::new(server.channel().await)
+        .echo(EchoMessage::default())
+        .await
+        .unwrap();
+    assert_eq!("hi", response.into_inner().text);
+}
+
+#[tokio::test]
+async fn generic_server_advertises_every_service() {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let server = GrpcServer::new(port)
+        .with_reflection(HELLO)
+        .with_reflection(MULTI)
+        .start()
+        .await;
+
+    let mut services = services(server.channel().await).await;
+    services.sort();
+    assert_eq!(
+        vec![
+            "Echo",
+            "Ping",
+            "grpc.reflection.v1.ServerReflection",
+            "hello.Greeter"
+        ],
+        services
+    );
+}
+
+#[tokio::test]
+async fn disabled_by_default() {
+    let server = EchoMockServer::start_default().await;
+
+    let status = reflect(
+        server.channel().await,
+        MessageRequest::ListServices(String::new()),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
@@ -0,0 +1,117 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use tonic::{transport::Endpoint, Code, Request};
+use wiremock_grpc::*;
+
+fn reply_with(server: &SharedServer, message: &'static str) -> MockBuilder {
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(move || HelloReply {
+                message: message.into(),
+            }),
+    )
+}
+
+async fn say_hello(server: &SharedServer) -> Result<String, Code> {
+    GreeterClient::new(server.channel().await)
+        .say_hello(HelloRequest::default())
+        .await
+        .map(|response| response.into_inner().message)
+        .map_err(|status| status.code())
+}
+
+#[tokio::test]
+async fn isolated_namespaces() {
+    let alice = GreeterMockServer::shared();
+    let bob = GreeterMockServer::shared();
+    assert_eq!(alice.address(), bob.address());
+    assert_ne!(alice.namespace(), bob.namespace());
+
+    let rule = reply_with(&alice, "Hello Alice");
+    reply_with(&bob, "Hello Bob");
+
+    assert_eq!(Ok("Hello Alice".into()), say_hello(&alice).await);
+    assert_eq!(Ok("Hello Bob".into()), say_hello(&bob).await);
+    assert_eq!(Ok("Hello Bob".into()), say_hello(&bob).await);
+
+    assert_eq!(1, alice.journal().len());
+    assert_eq!(2, bob.journal().len());
+    let request = alice.find_one(&rule);
+    assert_eq!(Some(alice.namespace()), request.namespace.as_deref());
+}
+
+#[tokio::test]
+async fn no_rule_in_the_namespace() {
+    let alice = GreeterMockServer::shared();
+    let bob = GreeterMockServer::shared();
+    reply_with(&alice, "Hello Alice");
+
+    assert_eq!(Err(Code::Unimplemented), say_hello(&bob).await);
+    assert_eq!(1, bob.journal().len());
+    assert!(alice.journal().is_empty());
+
+    alice.reset();
+}
+
+#[tokio::test]
+async fn namespace_metadata() {
+    let server = GreeterMockServer::shared();
+    reply_with(&server, "Hello");
+    let channel = Endpoint::from_shared(format!("http://{}", server.address()))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut client = GreeterClient::new(channel);
+
+    // outside of any namespace
+    let status = client.say_hello(HelloRequest::default()).await.unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+
+    let mut request = Request::new(HelloRequest::default());
+    request
+        .metadata_mut()
+        .insert(NAMESPACE_HEADER, server.namespace().parse().unwrap());
+    let response = client.say_hello(request).await.unwrap();
+    assert_eq!("Hello", response.into_inner().message);
+    assert_eq!(1, server.journal().len());
+}
+
+#[tokio::test]
+#[should_panic(expected = "dropped with unmatched rules")]
+async fn unmatched_rules() {
+    let server = GreeterMockServer::shared();
+    reply_with(&server, "Hello");
+}
+
+#[tokio::test]
+async fn removed_on_drop() {
+    let server = GreeterMockServer::shared();
+    reply_with(&server, "Hello");
+    say_hello(&server).await.unwrap();
+    let namespace = server.namespace().to_string();
+    drop(server);
+
+    let other = GreeterMockServer::shared();
+    let mut request = Request::new(HelloRequest::default());
+    request
+        .metadata_mut()
+        .insert(NAMESPACE_HEADER, namespace.parse().unwrap());
+    let status = GreeterClient::new(other.channel().await)
+        .say_hello(request)
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
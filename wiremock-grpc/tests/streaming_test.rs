@@ -0,0 +1,47 @@
+mod streaming {
+    tonic::include_proto!("streaming");
+    wiremock_grpc::include_mock!("streaming");
+}
+
+use std::time::Duration;
+
+use streaming::{chat_client::ChatClient, ChatMessage, ChatMockServer, ChatTypeSafeExt};
+use tokio::sync::mpsc;
+use tonic::codegen::tokio_stream::wrappers::ReceiverStream;
+use wiremock_grpc::*;
+
+#[tokio::test]
+async fn responds_before_the_request_stream_ends() {
+    let mut server = ChatMockServer::start_in_memory().await;
+    server.setup(
+        MockBuilder::when()
+            .path_echo()
+            .then()
+            .return_body(|| ChatMessage {
+                text: "pong".into(),
+            }),
+    );
+
+    // a ping-pong client: the stream stays open until it gets an answer
+    let (sender, receiver) = mpsc::channel(1);
+    sender
+        .send(ChatMessage {
+            text: "ping".into(),
+        })
+        .await
+        .unwrap();
+    let mut client = server.client(ChatClient::new).await;
+    let reply = tokio::time::timeout(Duration::from_secs(5), async {
+        let mut replies = client
+            .echo(ReceiverStream::new(receiver))
+            .await
+            .unwrap()
+            .into_inner();
+        replies.message().await.unwrap()
+    })
+    .await
+    .expect("No answer before the end of the request stream");
+
+    assert_eq!("pong", reply.unwrap().text);
+    drop(sender);
+}
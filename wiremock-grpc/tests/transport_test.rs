@@ -0,0 +1,84 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello(HelloRequest) -> HelloReply,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use wiremock_grpc::*;
+
+async fn say_hello(mut server: GreeterMockServer) {
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_body(|| HelloReply {
+                message: "Hello".into(),
+            }),
+    );
+
+    let mut client = server.client(GreeterClient::new).await;
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Test".into(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!("Hello", response.into_inner().message);
+}
+
+#[tokio::test]
+async fn tcp_channel() {
+    say_hello(GreeterMockServer::start_default().await).await;
+}
+
+#[tokio::test]
+async fn tcp_channel_all_interfaces() {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let server =
+        GreeterMockServer::start_with_addr(format!("0.0.0.0:{port}").parse().unwrap()).await;
+
+    say_hello(server).await;
+}
+
+#[tokio::test]
+async fn in_memory_channel() {
+    let server = GreeterMockServer::start_in_memory().await;
+
+    // multiple connections to the same server
+    let _ = server.channel().await;
+    say_hello(server).await;
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn uds_channel() {
+    let path = std::env::temp_dir().join(format!("wiremock-grpc-{}.sock", std::process::id()));
+    let server = GreeterMockServer::start_uds(&path).await;
+    assert!(path.exists());
+
+    say_hello(server).await;
+    assert!(!path.exists(), "the socket is removed with the server");
+}
+
+#[cfg(feature = "tls")]
+#[tokio::test]
+async fn tls_channel() {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+    let port = GrpcServer::find_unused_port().await.unwrap();
+
+    let server = GreeterMockServer::start_with(GrpcServer::with_tls(
+        format!("[::1]:{port}").parse().unwrap(),
+        cert.cert.pem(),
+        cert.signing_key.serialize_pem(),
+    ))
+    .await;
+
+    say_hello(server).await;
+}
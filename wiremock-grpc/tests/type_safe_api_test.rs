@@ -30,12 +30,7 @@ async fn proc_macro_rpc_method() {
             }),
     );
 
-    let channel =
-        tonic::transport::Channel::from_shared(format!("http://[::1]:{}", server.address().port()))
-            .unwrap()
-            .connect()
-            .await
-            .unwrap();
+    let channel = server.channel().await;
     let mut client = GreeterClient::new(channel);
 
     let response = client
@@ -139,14 +134,7 @@ mod custom_server_test {
                 }),
         );
 
-        let channel = tonic::transport::Channel::from_shared(format!(
-            "http://[::1]:{}",
-            server.address().port()
-        ))
-        .unwrap()
-        .connect()
-        .await
-        .unwrap();
+        let channel = server.channel().await;
         let mut client = GreeterClient::new(channel);
 
         let response = client
@@ -190,14 +178,7 @@ mod nested_package_test {
                 }),
         );
 
-        let channel = tonic::transport::Channel::from_shared(format!(
-            "http://[::1]:{}",
-            server.address().port()
-        ))
-        .unwrap()
-        .connect()
-        .await
-        .unwrap();
+        let channel = server.channel().await;
         let mut client = ExtendedGreeterClient::new(channel);
 
         let response = client
@@ -211,14 +192,183 @@ mod nested_package_test {
     }
 }
 
-async fn create() -> (GreeterMockServer, GreeterClient<Channel>) {
-    let server = GreeterMockServer::start_default().await;
+mod typed_test {
+    use super::hello::{
+        greeter_client::GreeterClient, HelloReply, HelloRequest, WeatherReply, WeatherRequest,
+    };
+    use tonic::Code;
+    use wiremock_grpc::*;
+
+    wiremock_grpc::generate_svc! {
+        package hello;
+        service Greeter as TypedServer {
+            SayHello(HelloRequest) -> HelloReply,
+            WeatherInfo(WeatherRequest) -> WeatherReply,
+        }
+    }
+
+    #[tokio::test]
+    async fn typed_builder_matches_request_body() {
+        let mut server = TypedServer::start_default().await;
+
+        let london = server.setup(
+            MockBuilder::when()
+                .path_weather_info()
+                .body_eq(WeatherRequest {
+                    city: "London".into(),
+                })
+                .then()
+                .return_body(|| WeatherReply {
+                    weather: "Rainy".into(),
+                }),
+        );
+        let paris = server.setup(
+            MockBuilder::when()
+                .path_weather_info()
+                .body_contains(WeatherRequest {
+                    city: "Paris".into(),
+                })
+                .then()
+                .return_body(|| WeatherReply {
+                    weather: "Sunny".into(),
+                }),
+        );
 
-    let channel =
-        tonic::transport::Channel::from_shared(format!("http://[::1]:{}", server.address().port()))
-            .unwrap()
-            .connect()
+        let channel = server.channel().await;
+        let mut client = GreeterClient::new(channel);
+
+        let response = client
+            .weather_info(WeatherRequest {
+                city: "Paris".into(),
+            })
             .await
             .unwrap();
+        assert_eq!("Sunny", response.into_inner().weather);
+
+        let response = client
+            .weather_info(WeatherRequest {
+                city: "London".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!("Rainy", response.into_inner().weather);
+
+        let response = client
+            .weather_info(WeatherRequest {
+                city: "Berlin".into(),
+            })
+            .await;
+        assert_eq!(Code::Unimplemented, response.unwrap_err().code());
+
+        let request = server.find_one(&london);
+        assert_eq!(
+            "London",
+            request.decode_body::<WeatherRequest>().unwrap().city
+        );
+        let _ = server.find_one(&paris);
+    }
+
+    #[tokio::test]
+    async fn typed_builder_returns_status_and_headers() {
+        let mut server = TypedServer::start_default().await;
+
+        server.setup(
+            MockBuilder::when()
+                .path_say_hello()
+                .header("x-session-id", "abc")
+                .then()
+                .return_status(Code::Ok)
+                .return_header("x-rate-limit", "10")
+                .return_body(|| HelloReply {
+                    message: "Typed!".into(),
+                }),
+        );
+
+        let channel = server.channel().await;
+        let mut client = GreeterClient::new(channel);
+
+        let mut request = tonic::Request::new(HelloRequest {
+            name: "Test".into(),
+        });
+        request
+            .metadata_mut()
+            .insert("x-session-id", "abc".parse().unwrap());
+        let response = client.say_hello(request).await.unwrap();
+
+        assert_eq!("10", response.metadata().get("x-rate-limit").unwrap());
+        assert_eq!("Typed!", response.into_inner().message);
+    }
+}
+
+mod proto_file_test {
+    use super::hello::{
+        greeter_client::GreeterClient, HelloReply, HelloRequest, WeatherReply, WeatherRequest,
+    };
+    use wiremock_grpc::*;
+
+    wiremock_grpc::generate_svc!(proto = "proto/hello.proto", service = "Greeter");
+
+    mod custom_name {
+        wiremock_grpc::generate_svc!(
+            proto = "proto/extended.proto",
+            service = "ExtendedGreeter",
+            server = ProtoServer,
+        );
+    }
+
+    #[tokio::test]
+    async fn proc_macro_from_proto_file() {
+        let mut server = GreeterMockServer::start_default().await;
+
+        server.setup(
+            MockBuilder::when()
+                .path_say_hello()
+                .then()
+                .return_body(|| HelloReply {
+                    message: "From proto!".into(),
+                }),
+        );
+        server.setup(
+            MockBuilder::when()
+                .path_weather_info()
+                .then()
+                .return_body(|| WeatherReply {
+                    weather: "Cloudy".into(),
+                }),
+        );
+
+        let channel = server.channel().await;
+        let mut client = GreeterClient::new(channel);
+
+        let response = client
+            .say_hello(HelloRequest {
+                name: "Test".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!("From proto!", response.into_inner().message);
+
+        let response = client
+            .weather_info(WeatherRequest {
+                city: "London".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!("Cloudy", response.into_inner().weather);
+    }
+
+    #[tokio::test]
+    async fn proc_macro_from_proto_file_custom_server_name() {
+        use custom_name::*;
+
+        let server = ProtoServer::start_default().await;
+        assert!(std::net::TcpStream::connect(server.address()).is_ok());
+    }
+}
+
+async fn create() -> (GreeterMockServer, GreeterClient<Channel>) {
+    let server = GreeterMockServer::start_default().await;
+
+    let channel = server.channel().await;
     (server, GreeterClient::new(channel))
 }
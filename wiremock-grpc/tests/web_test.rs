@@ -0,0 +1,128 @@
+mod hello {
+    tonic::include_proto!("hello");
+}
+
+wiremock_grpc::generate_svc! {
+    package hello;
+    service Greeter {
+        SayHello,
+    }
+}
+
+use hello::{greeter_client::GreeterClient, HelloReply, HelloRequest};
+use http_body_util::Empty;
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::TokioExecutor,
+};
+use tonic::{body::Body, codegen::http, Code};
+use tonic_web::{GrpcWebCall, GrpcWebClientLayer, GrpcWebClientService};
+use tower::ServiceBuilder;
+use wiremock_grpc::*;
+
+type WebClient = GreeterClient<GrpcWebClientService<Client<HttpConnector, GrpcWebCall<Body>>>>;
+
+async fn start() -> (GreeterMockServer, WebClient) {
+    let port = GrpcServer::find_unused_port().await.unwrap();
+    let server = GreeterMockServer::start_with(GrpcServer::new(port).with_grpc_web()).await;
+
+    // an HTTP/1.1 only client
+    let client = Client::builder(TokioExecutor::new()).build_http();
+    let service = ServiceBuilder::new()
+        .layer(GrpcWebClientLayer::new())
+        .service(client);
+    let origin = format!("http://{}", server.address()).parse().unwrap();
+    (server, GreeterClient::with_origin(service, origin))
+}
+
+#[tokio::test]
+async fn grpc_web_call() {
+    let (mut server, mut client) = start().await;
+
+    let rule = server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .then()
+            .return_header("x-server", "mock")
+            .return_trailer("x-trace-id", "42")
+            .return_body(|| HelloReply {
+                message: "Hello".into(),
+            }),
+    );
+
+    let response = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!("mock", response.metadata().get("x-server").unwrap());
+    assert_eq!("42", response.metadata().get("x-trace-id").unwrap());
+    assert_eq!("Hello", response.into_inner().message);
+
+    let request = server.find_one(&rule);
+    assert_eq!("Alice", request.decode_body::<HelloRequest>().unwrap().name);
+}
+
+#[tokio::test]
+async fn grpc_web_error() {
+    let (mut server, mut client) = start().await;
+
+    server.setup(
+        MockBuilder::when()
+            .path_say_hello()
+            .body_eq(HelloRequest {
+                name: "Alice".into(),
+            })
+            .then()
+            .return_status(Code::PermissionDenied),
+    );
+
+    let status = client
+        .say_hello(HelloRequest {
+            name: "Alice".into(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::PermissionDenied, status.code());
+
+    let status = client
+        .say_hello(HelloRequest { name: "Bob".into() })
+        .await
+        .unwrap_err();
+    assert_eq!(Code::Unimplemented, status.code());
+}
+
+#[tokio::test]
+async fn cors_preflight() {
+    let (server, _) = start().await;
+
+    let request = http::Request::builder()
+        .method(http::Method::OPTIONS)
+        .uri(format!(
+            "http://{}/hello.Greeter/SayHello",
+            server.address()
+        ))
+        .header("origin", "http://localhost:8080")
+        .header("access-control-request-method", "POST")
+        .header(
+            "access-control-request-headers",
+            "content-type,x-grpc-web,x-user-agent",
+        )
+        .body(Empty::<tonic::codegen::Bytes>::new())
+        .unwrap();
+    let response = Client::builder(TokioExecutor::new())
+        .build_http()
+        .request(request)
+        .await
+        .unwrap();
+
+    assert!(response.status().is_success());
+    assert_eq!(
+        "*",
+        response.headers()["access-control-allow-origin"]
+            .to_str()
+            .unwrap()
+    );
+    assert!(server.journal().is_empty());
+}